@@ -1,17 +1,15 @@
 use crate::adc::adc_sample::ADCSample;
 use crate::adc::{adc_service, ADCChannel, ADCConfig, ADCData, ADCPulseInError, ADCReadError};
 use crate::iowarrior::Peripheral;
-use crate::iowarrior::{peripheral_service, IOWarriorData, IOWarriorMutData};
+use crate::iowarrior::{peripheral_service, IOWarriorData, IOWarriorMutData, Shared, SharedCell};
 use embedded_hal::digital::PinState;
-use std::cell::RefCell;
 use std::fmt;
-use std::rc::Rc;
 use std::time::Duration;
 
 #[derive(Debug)]
 pub struct ADC {
-    pub(crate) data: Rc<IOWarriorData>,
-    pub(crate) mut_data_refcell: Rc<RefCell<IOWarriorMutData>>,
+    pub(crate) data: Shared<IOWarriorData>,
+    pub(crate) mut_data_refcell: Shared<SharedCell<IOWarriorMutData>>,
     pub(crate) adc_data: ADCData,
 }
 
@@ -155,17 +155,17 @@ impl ADCChannel {
     }
 
     #[inline]
-    pub fn from_u8(channel: u8) -> ADCChannel {
+    pub fn from_u8(channel: u8) -> Option<ADCChannel> {
         match channel {
-            1 => ADCChannel::First,
-            2 => ADCChannel::Second,
-            3 => ADCChannel::Third,
-            4 => ADCChannel::Fourth,
-            5 => ADCChannel::Fifth,
-            6 => ADCChannel::Sixth,
-            7 => ADCChannel::Seventh,
-            8 => ADCChannel::Eighth,
-            _ => panic!("Channel {} is not existing", channel),
+            1 => Some(ADCChannel::First),
+            2 => Some(ADCChannel::Second),
+            3 => Some(ADCChannel::Third),
+            4 => Some(ADCChannel::Fourth),
+            5 => Some(ADCChannel::Fifth),
+            6 => Some(ADCChannel::Sixth),
+            7 => Some(ADCChannel::Seventh),
+            8 => Some(ADCChannel::Eighth),
+            _ => None,
         }
     }
 }
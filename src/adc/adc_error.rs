@@ -1,4 +1,5 @@
 use crate::adc::ADCChannel;
+use crate::iowarrior::ReportId;
 use hidapi::HidError;
 use thiserror::Error;
 
@@ -9,6 +10,8 @@ pub enum ADCReadError {
     PacketLoss,
     #[error("USB HID error.")]
     ErrorUSB(HidError),
+    #[error("ADC expected report {expected}, got {got:#04x}.")]
+    ProtocolError { expected: ReportId, got: u8 },
 }
 
 #[non_exhaustive]
@@ -22,4 +25,6 @@ pub enum ADCPulseInError {
     PulseTimeout,
     #[error("ADC channel {0} is not enabled in current config.")]
     InvalidChannel(ADCChannel),
+    #[error("ADC expected report {expected}, got {got:#04x}.")]
+    ProtocolError { expected: ReportId, got: u8 },
 }
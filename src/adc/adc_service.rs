@@ -6,19 +6,17 @@ use crate::adc::{
 use crate::communication::communication_service;
 use crate::iowarrior::{
     peripheral_service, IOWarriorData, IOWarriorMutData, Peripheral, PeripheralSetupError, Pipe,
-    ReportId,
+    ReportId, Shared, SharedCell, SharedRefMut,
 };
 use crate::{iowarrior::IOWarriorType, pin};
 use embedded_hal::digital::PinState;
 use hidapi::HidError;
-use std::cell::{RefCell, RefMut};
 use std::ops::Not;
-use std::rc::Rc;
 use std::time::Duration;
 
 pub fn new(
-    data: &Rc<IOWarriorData>,
-    mut_data_refcell: &Rc<RefCell<IOWarriorMutData>>,
+    data: &Shared<IOWarriorData>,
+    mut_data_refcell: &Shared<SharedCell<IOWarriorMutData>>,
     adc_config: ADCConfig,
 ) -> Result<ADC, PeripheralSetupError> {
     match get_adc_type(&data) {
@@ -56,7 +54,7 @@ pub fn new(
 
             peripheral_service::post_enable(&mut mut_data, &adc_pins, Peripheral::ADC);
 
-            let adc_data_refcell = Rc::new(RefCell::new(adc_data));
+            let adc_data_refcell = Shared::new(SharedCell::new(adc_data));
 
             Ok(ADC {
                 data: data.clone(),
@@ -67,7 +65,7 @@ pub fn new(
     }
 }
 
-fn get_adc_type(data: &Rc<IOWarriorData>) -> Option<IOWarriorADCType> {
+fn get_adc_type(data: &Shared<IOWarriorData>) -> Option<IOWarriorADCType> {
     match data.device_type {
         IOWarriorType::IOWarrior28 => Some(IOWarriorADCType::IOWarrior28),
         IOWarriorType::IOWarrior100 => Some(IOWarriorADCType::IOWarrior100),
@@ -230,7 +228,7 @@ fn get_adc_pins(adc_data: &ADCData) -> Vec<u8> {
 
 fn send_enable_adc(
     data: &IOWarriorData,
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
     adc_data: &ADCData,
 ) -> Result<(), HidError> {
     let mut report = data.create_report(Pipe::ADCMode);
@@ -258,8 +256,8 @@ fn send_enable_adc(
 }
 
 pub fn read_samples(
-    data: &Rc<IOWarriorData>,
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    data: &Shared<IOWarriorData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
     adc_data: &ADCData,
     buffer: &mut [Option<ADCSample>],
 ) -> Result<(), ADCReadError> {
@@ -273,8 +271,8 @@ pub fn read_samples(
 }
 
 pub fn pulse_in(
-    data: &Rc<IOWarriorData>,
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    data: &Shared<IOWarriorData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
     adc_data: &ADCData,
     channel: ADCChannel,
     pin_state: PinState,
@@ -293,6 +291,9 @@ pub fn pulse_in(
             |x| match x {
                 ADCReadError::PacketLoss => ADCPulseInError::PacketLoss,
                 ADCReadError::ErrorUSB(y) => ADCPulseInError::ErrorUSB(y),
+                ADCReadError::ProtocolError { expected, got } => {
+                    ADCPulseInError::ProtocolError { expected, got }
+                }
             },
         )?;
 
@@ -367,18 +368,26 @@ fn get_pin_state(adc_sample: &ADCSample, adc_data: &ADCData) -> PinState {
 }
 
 fn read_samples_report(
-    data: &Rc<IOWarriorData>,
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    data: &Shared<IOWarriorData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
     adc_data: &ADCData,
     buffer: &mut [Option<ADCSample>],
     last_packet: &mut Option<u8>,
 ) -> Result<(), ADCReadError> {
-    let report = communication_service::read_report(
+    let report = communication_service::read_report_by_id(
         &mut mut_data.communication_data,
         data.create_report(Pipe::ADCMode),
+        &[ReportId::AdcRead],
     )
     .map_err(|x| ADCReadError::ErrorUSB(x))?;
 
+    if report.buffer[0] != ReportId::AdcRead.get_value() {
+        return Err(ADCReadError::ProtocolError {
+            expected: ReportId::AdcRead,
+            got: report.buffer[0],
+        });
+    }
+
     update_packet_number(last_packet, report.buffer[1])?;
 
     let mut sample_counter = 0u8;
@@ -389,10 +398,7 @@ fn read_samples_report(
         let value = u16::from_le_bytes([from[0], from[1]]);
         let raw_channel = (sample_counter % adc_data.highest_enabled_channel.get_value()) + 1;
 
-        *to = Some(ADCSample {
-            channel: ADCChannel::from_u8(raw_channel),
-            value,
-        });
+        *to = ADCChannel::from_u8(raw_channel).map(|channel| ADCSample { channel, value });
     }
 
     Ok(())
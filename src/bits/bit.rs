@@ -20,8 +20,23 @@ impl fmt::Display for Bit {
 
 impl Bit {
     #[inline]
-    pub fn from_u8(bit_index: u8) -> Bit {
+    pub fn from_u8(bit_index: u8) -> Option<Bit> {
         match bit_index {
+            0 => Some(Bit::Bit0),
+            1 => Some(Bit::Bit1),
+            2 => Some(Bit::Bit2),
+            3 => Some(Bit::Bit3),
+            4 => Some(Bit::Bit4),
+            5 => Some(Bit::Bit5),
+            6 => Some(Bit::Bit6),
+            7 => Some(Bit::Bit7),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn from_pin(pin: u8) -> Bit {
+        match pin % 8 {
             0 => Bit::Bit0,
             1 => Bit::Bit1,
             2 => Bit::Bit2,
@@ -29,8 +44,7 @@ impl Bit {
             4 => Bit::Bit4,
             5 => Bit::Bit5,
             6 => Bit::Bit6,
-            7 => Bit::Bit7,
-            _ => panic!("bit index {} is out of bounds for u8", bit_index),
+            _ => Bit::Bit7,
         }
     }
 
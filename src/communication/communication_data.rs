@@ -0,0 +1,24 @@
+use crate::communication::{Backend, ReconnectData, Transport};
+use crate::iowarrior::Report;
+use std::collections::VecDeque;
+use std::fmt;
+use std::time::Instant;
+
+#[derive(Debug)]
+pub struct CommunicationData {
+    pub transport: Box<dyn Transport>,
+    pub backend: Option<Backend>,
+    pub device_path: Option<String>,
+    pub reconnect_data: Option<ReconnectData>,
+    pub replay_reports: Vec<Report>,
+    pub disconnected_since: Option<Instant>,
+    pub last_reconnect_attempt: Option<Instant>,
+    pub pending_pins_report: Option<Report>,
+    pub queued_reports: VecDeque<Report>,
+}
+
+impl fmt::Display for CommunicationData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
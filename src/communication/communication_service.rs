@@ -0,0 +1,340 @@
+use crate::communication::{
+    initialization_service, CommunicationData, DisconnectedTransport, DISCONNECTED_MESSAGE,
+};
+use crate::iowarrior::{iowarrior_service, Pipe, Report, ReportId};
+use hidapi::HidError;
+use std::io::ErrorKind;
+use std::time::{Duration, Instant};
+
+const RECONNECT_INTERVAL: Duration = Duration::from_millis(100);
+const MAX_QUEUED_REPORTS: usize = 64;
+
+pub fn write_report(
+    communication_data: &mut CommunicationData,
+    report: &Report,
+) -> Result<(), HidError> {
+    match communication_data.transport.write_report(report) {
+        Ok(_) => {}
+        Err(error) => {
+            if !try_reconnect(communication_data, &error) {
+                return Err(error);
+            }
+
+            communication_data.transport.write_report(report)?;
+        }
+    }
+
+    record_replay_report(communication_data, report);
+
+    Ok(())
+}
+
+pub fn read_report_non_blocking(
+    communication_data: &mut CommunicationData,
+    report: Report,
+) -> Result<Option<Report>, HidError> {
+    if let Some(x) = take_pending_report(communication_data, &report) {
+        return Ok(Some(x));
+    }
+
+    match communication_data
+        .transport
+        .read_report_non_blocking(report.clone())
+    {
+        Ok(x) => Ok(x),
+        Err(error) => {
+            if !try_reconnect(communication_data, &error) {
+                return Err(error);
+            }
+
+            // Retried once, a second failure is returned without reconnecting again.
+            match take_pending_report(communication_data, &report) {
+                Some(x) => Ok(Some(x)),
+                None => communication_data.transport.read_report_non_blocking(report),
+            }
+        }
+    }
+}
+
+pub fn read_report(
+    communication_data: &mut CommunicationData,
+    report: Report,
+) -> Result<Report, HidError> {
+    if let Some(x) = take_pending_report(communication_data, &report) {
+        return Ok(x);
+    }
+
+    match communication_data.transport.read_report(report) {
+        Ok(x) => Ok(x),
+        Err(error) => {
+            // The answer to a request sent before the disconnect is lost, so the read is not retried.
+            try_reconnect(communication_data, &error);
+
+            Err(error)
+        }
+    }
+}
+
+pub fn read_report_timeout(
+    communication_data: &mut CommunicationData,
+    report: Report,
+    timeout: Duration,
+) -> Result<Option<Report>, HidError> {
+    if let Some(x) = take_pending_report(communication_data, &report) {
+        return Ok(Some(x));
+    }
+
+    match communication_data
+        .transport
+        .read_report_timeout(report.clone(), timeout)
+    {
+        Ok(x) => Ok(x),
+        Err(error) => {
+            if !try_reconnect(communication_data, &error) {
+                return Err(error);
+            }
+
+            match take_pending_report(communication_data, &report) {
+                Some(x) => Ok(Some(x)),
+                None => communication_data
+                    .transport
+                    .read_report_timeout(report, timeout),
+            }
+        }
+    }
+}
+
+// Several peripherals share the special mode interface on some boards, so reports of another
+// peripheral are queued for it. Reports with an unknown id are returned to the caller.
+pub fn read_report_by_id(
+    communication_data: &mut CommunicationData,
+    report: Report,
+    report_ids: &[ReportId],
+) -> Result<Report, HidError> {
+    if let Some(x) = take_queued_report(communication_data, report_ids) {
+        return Ok(x);
+    }
+
+    loop {
+        let x = read_report(communication_data, report.clone())?;
+
+        if !queue_foreign_report(communication_data, &x, report_ids) {
+            return Ok(x);
+        }
+    }
+}
+
+pub fn read_report_by_id_timeout(
+    communication_data: &mut CommunicationData,
+    report: Report,
+    report_ids: &[ReportId],
+    timeout: Duration,
+) -> Result<Option<Report>, HidError> {
+    if let Some(x) = take_queued_report(communication_data, report_ids) {
+        return Ok(Some(x));
+    }
+
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        let x = match read_report_timeout(
+            communication_data,
+            report.clone(),
+            deadline.saturating_duration_since(Instant::now()),
+        )? {
+            None => return Ok(None),
+            Some(x) => x,
+        };
+
+        if !queue_foreign_report(communication_data, &x, report_ids) {
+            return Ok(Some(x));
+        }
+    }
+}
+
+pub fn take_queued_report(
+    communication_data: &mut CommunicationData,
+    report_ids: &[ReportId],
+) -> Option<Report> {
+    let index = communication_data
+        .queued_reports
+        .iter()
+        .position(|x| report_ids.iter().any(|y| y.get_value() == x.buffer[0]))?;
+
+    communication_data.queued_reports.remove(index)
+}
+
+// Queued and unread reports with one of the ids are dropped, the others are queued.
+pub fn discard_reports(
+    communication_data: &mut CommunicationData,
+    report: Report,
+    report_ids: &[ReportId],
+) -> Result<(), HidError> {
+    communication_data
+        .queued_reports
+        .retain(|x| !report_ids.iter().any(|y| y.get_value() == x.buffer[0]));
+
+    while let Some(x) = read_report_non_blocking(communication_data, report.clone())? {
+        queue_foreign_report(communication_data, &x, report_ids);
+    }
+
+    Ok(())
+}
+
+fn queue_foreign_report(
+    communication_data: &mut CommunicationData,
+    report: &Report,
+    report_ids: &[ReportId],
+) -> bool {
+    let is_foreign = match ReportId::from_u8(report.buffer[0]) {
+        None => false,
+        Some(x) => !report_ids.contains(&x),
+    };
+
+    if is_foreign {
+        if communication_data.queued_reports.len() >= MAX_QUEUED_REPORTS {
+            communication_data.queued_reports.pop_front();
+        }
+
+        communication_data.queued_reports.push_back(report.clone());
+    }
+
+    is_foreign
+}
+
+fn take_pending_report(
+    communication_data: &mut CommunicationData,
+    report: &Report,
+) -> Option<Report> {
+    match report.pipe {
+        Pipe::IOPins => communication_data.pending_pins_report.take(),
+        Pipe::SpecialMode | Pipe::I2CMode | Pipe::ADCMode => None,
+    }
+}
+
+fn record_replay_report(communication_data: &mut CommunicationData, report: &Report) {
+    let is_replayed = match report.pipe {
+        Pipe::IOPins => true,
+        Pipe::SpecialMode | Pipe::I2CMode | Pipe::ADCMode => matches!(
+            ReportId::from_u8(report.buffer[0]),
+            Some(
+                ReportId::I2cSetup
+                    | ReportId::IrSetup
+                    | ReportId::SpiSetup
+                    | ReportId::PwmSetup
+                    | ReportId::PwmParameters
+                    | ReportId::AdcSetup
+            )
+        ),
+    };
+
+    if is_replayed {
+        communication_data
+            .replay_reports
+            .retain(|x| x.pipe != report.pipe || x.buffer[0] != report.buffer[0]);
+
+        communication_data.replay_reports.push(report.clone());
+    }
+}
+
+fn is_disconnect_error(error: &HidError) -> bool {
+    match error {
+        HidError::IoError { error } => {
+            // ENODEV on Linux, ERROR_DEVICE_NOT_CONNECTED on Windows.
+            matches!(error.raw_os_error(), Some(19) | Some(1167))
+                || matches!(
+                    error.kind(),
+                    ErrorKind::NotFound
+                        | ErrorKind::NotConnected
+                        | ErrorKind::BrokenPipe
+                        | ErrorKind::ConnectionReset
+                        | ErrorKind::ConnectionAborted
+                )
+        }
+        // Nothing at all is transferred once the device is gone.
+        HidError::IncompleteSendError { sent, .. } => *sent == 0,
+        HidError::HidApiError { message } => {
+            let message = message.to_lowercase();
+
+            message == DISCONNECTED_MESSAGE.to_lowercase()
+                || message.contains("no such device")
+                || message.contains("not connected")
+        }
+        _ => false,
+    }
+}
+
+fn try_reconnect(communication_data: &mut CommunicationData, error: &HidError) -> bool {
+    let reconnect_data = match &communication_data.reconnect_data {
+        None => return false,
+        Some(x) => x.clone(),
+    };
+
+    // Boards created from a custom transport have no backend to reopen them.
+    let backend = match communication_data.backend {
+        None => return false,
+        Some(x) => x,
+    };
+
+    if !is_disconnect_error(error) {
+        return false;
+    }
+
+    let now = Instant::now();
+
+    let disconnected_since = *communication_data.disconnected_since.get_or_insert(now);
+
+    if now.duration_since(disconnected_since) > reconnect_data.timeout {
+        return false;
+    }
+
+    // Every call makes at most one attempt without sleeping, so the board lock is not held while waiting.
+    if let Some(last_attempt) = communication_data.last_reconnect_attempt {
+        if now.duration_since(last_attempt) < RECONNECT_INTERVAL {
+            return false;
+        }
+    }
+
+    communication_data.last_reconnect_attempt = Some(now);
+
+    // Some backends allow only a single open handle per device, so the old one is released first.
+    communication_data.transport = Box::new(DisconnectedTransport);
+
+    // Only the original backend and device path are tried, other boards are not opened.
+    let iowarrior = match initialization_service::open_iowarrior(
+        backend,
+        &reconnect_data.device_serial,
+        communication_data.device_path.as_deref(),
+    ) {
+        Ok(x) => x,
+        Err(_) => return false,
+    };
+
+    if iowarrior.get_type() != reconnect_data.device_type {
+        return false;
+    }
+
+    let (data, mut transport) = match iowarrior_service::into_transport(iowarrior) {
+        None => return false,
+        Some(x) => x,
+    };
+
+    for report in &communication_data.replay_reports {
+        if transport.write_report(report).is_err() {
+            return false;
+        }
+    }
+
+    // The pins may have changed while the board was gone.
+    let pins_report = match iowarrior_service::get_pins_report(&data, transport.as_mut()) {
+        Ok(x) => x,
+        Err(_) => return false,
+    };
+
+    communication_data.transport = transport;
+    communication_data.pending_pins_report = Some(pins_report);
+    communication_data.disconnected_since = None;
+    communication_data.last_reconnect_attempt = None;
+
+    true
+}
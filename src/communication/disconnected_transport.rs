@@ -0,0 +1,44 @@
+use crate::communication::Transport;
+use crate::iowarrior::Report;
+use hidapi::HidError;
+use std::fmt;
+use std::time::Duration;
+
+pub const DISCONNECTED_MESSAGE: &str = "IOWarrior is disconnected.";
+
+#[derive(Debug)]
+pub struct DisconnectedTransport;
+
+impl fmt::Display for DisconnectedTransport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Transport for DisconnectedTransport {
+    fn write_report(&mut self, report: &Report) -> Result<(), HidError> {
+        Err(disconnected_error())
+    }
+
+    fn read_report(&mut self, report: Report) -> Result<Report, HidError> {
+        Err(disconnected_error())
+    }
+
+    fn read_report_non_blocking(&mut self, report: Report) -> Result<Option<Report>, HidError> {
+        Err(disconnected_error())
+    }
+
+    fn read_report_timeout(
+        &mut self,
+        report: Report,
+        timeout: Duration,
+    ) -> Result<Option<Report>, HidError> {
+        Err(disconnected_error())
+    }
+}
+
+fn disconnected_error() -> HidError {
+    HidError::HidApiError {
+        message: DISCONNECTED_MESSAGE.to_owned(),
+    }
+}
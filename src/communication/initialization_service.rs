@@ -0,0 +1,109 @@
+#[cfg(feature = "ioctrl")]
+use crate::communication::ioctrl;
+#[cfg(feature = "iowkit")]
+use crate::communication::iowkit;
+#[cfg(feature = "simulator")]
+use crate::communication::simulator;
+#[cfg(feature = "usbhid")]
+use crate::communication::usbhid;
+#[allow(unused_imports)]
+use crate::communication::Backend;
+use crate::communication::{IOWarriorDescriptor, InitializationError};
+use crate::iowarrior::IOWarrior;
+
+#[cfg(feature = "simulator")]
+pub use crate::communication::simulator::get_simulated_iowarrior;
+
+pub fn get_iowarriors() -> Result<Vec<IOWarrior>, InitializationError> {
+    #[allow(unused_mut)]
+    let mut vec: Vec<IOWarrior> = Vec::new();
+
+    #[cfg(feature = "iowkit")]
+    vec.extend(iowkit::get_iowarriors()?);
+
+    #[cfg(feature = "usbhid")]
+    vec.extend(usbhid::get_iowarriors()?);
+
+    #[cfg(feature = "ioctrl")]
+    vec.extend(ioctrl::get_iowarriors()?);
+
+    #[cfg(feature = "simulator")]
+    vec.extend(simulator::get_iowarriors()?);
+
+    Ok(vec)
+}
+
+pub fn get_iowarrior(serial_number: &str) -> Result<IOWarrior, InitializationError> {
+    #[cfg(feature = "iowkit")]
+    match iowkit::get_iowarrior(serial_number) {
+        Err(InitializationError::NotFound(_)) => {}
+        result => return result,
+    }
+
+    #[cfg(feature = "usbhid")]
+    match usbhid::get_iowarrior(serial_number) {
+        Err(InitializationError::NotFound(_)) => {}
+        result => return result,
+    }
+
+    #[cfg(feature = "ioctrl")]
+    match ioctrl::get_iowarrior(serial_number) {
+        Err(InitializationError::NotFound(_)) => {}
+        result => return result,
+    }
+
+    #[cfg(feature = "simulator")]
+    match simulator::get_iowarrior(serial_number) {
+        Err(InitializationError::NotFound(_)) => {}
+        result => return result,
+    }
+
+    Err(InitializationError::NotFound(String::from(serial_number)))
+}
+
+pub fn list_iowarriors() -> Result<Vec<IOWarriorDescriptor>, InitializationError> {
+    #[allow(unused_mut)]
+    let mut vec: Vec<IOWarriorDescriptor> = Vec::new();
+
+    #[cfg(feature = "iowkit")]
+    vec.extend(iowkit::list_iowarriors()?);
+
+    #[cfg(feature = "usbhid")]
+    vec.extend(usbhid::list_iowarriors()?);
+
+    #[cfg(feature = "ioctrl")]
+    vec.extend(ioctrl::list_iowarriors()?);
+
+    #[cfg(feature = "simulator")]
+    vec.extend(simulator::list_iowarriors()?);
+
+    Ok(vec)
+}
+
+// Only the listed board is opened, boards of other processes are left alone.
+pub fn open(descriptor: &IOWarriorDescriptor) -> Result<IOWarrior, InitializationError> {
+    open_iowarrior(
+        descriptor.backend,
+        &descriptor.device_serial,
+        descriptor.device_path.as_deref(),
+    )
+}
+
+pub(crate) fn open_iowarrior(
+    backend: Backend,
+    serial_number: &str,
+    device_path: Option<&str>,
+) -> Result<IOWarrior, InitializationError> {
+    match backend {
+        #[cfg(feature = "iowkit")]
+        Backend::Iowkit => iowkit::open_iowarrior(serial_number, device_path),
+        #[cfg(feature = "usbhid")]
+        Backend::Usbhid => usbhid::open_iowarrior(serial_number, device_path),
+        #[cfg(feature = "ioctrl")]
+        Backend::Ioctrl => ioctrl::open_iowarrior(serial_number, device_path),
+        #[cfg(feature = "simulator")]
+        Backend::Simulator => simulator::open_iowarrior(serial_number, device_path),
+        #[allow(unreachable_patterns)]
+        _ => Err(InitializationError::NotFound(String::from(serial_number))),
+    }
+}
@@ -34,11 +34,11 @@ impl fmt::Display for USBPipes {
 }
 
 #[derive(Debug)]
-pub struct CommunicationData {
+pub struct IoctrlTransport {
     pub usb_pipes: USBPipes,
 }
 
-impl fmt::Display for CommunicationData {
+impl fmt::Display for IoctrlTransport {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:?}", self)
     }
@@ -1,66 +1,92 @@
-use crate::communication::{CommunicationData, USBPipe, USBPipes};
+use crate::communication::ioctrl::{IoctrlTransport, USBPipe, USBPipes};
+use crate::communication::Transport;
 use crate::iowarrior::{Pipe, Report};
 use hidapi::HidError;
+use nix::poll::{poll, PollFd, PollFlags, PollTimeout};
 use std::io::{Read, Write};
+use std::os::fd::AsFd;
+use std::time::Duration;
 
-pub fn write_report(
-    communication_data: &mut CommunicationData,
-    report: &Report,
-) -> Result<(), HidError> {
-    let usb_device = pipe_to_usb_device(&mut communication_data.usb_pipes, report.pipe);
-
-    let bytes_written = usb_device
-        .file
-        .write(&report.buffer[0..])
-        .map_err(|x| HidError::IoError { error: x })?;
-
-    if bytes_written != report.buffer.len() {
-        return Err(HidError::IncompleteSendError {
-            sent: bytes_written,
-            all: report.buffer.len(),
-        });
+impl Transport for IoctrlTransport {
+    fn write_report(&mut self, report: &Report) -> Result<(), HidError> {
+        let usb_device = pipe_to_usb_device(&mut self.usb_pipes, report.pipe);
+
+        let bytes_written = usb_device
+            .file
+            .write(&report.buffer[0..])
+            .map_err(|x| HidError::IoError { error: x })?;
+
+        if bytes_written != report.buffer.len() {
+            return Err(HidError::IncompleteSendError {
+                sent: bytes_written,
+                all: report.buffer.len(),
+            });
+        }
+
+        Ok(())
     }
 
-    Ok(())
-}
+    fn read_report(&mut self, mut report: Report) -> Result<Report, HidError> {
+        let usb_device = pipe_to_usb_device(&mut self.usb_pipes, report.pipe);
 
-pub fn read_report_non_blocking(
-    communication_data: &mut CommunicationData,
-    mut report: Report,
-) -> Result<Option<Report>, HidError> {
-    let usb_device = pipe_to_usb_device(&mut communication_data.usb_pipes, report.pipe);
+        let bytes_read = usb_device.file.read(report.buffer.as_mut_slice())?;
 
-    let bytes_read = usb_device.file.read(report.buffer.as_mut_slice())?;
+        if bytes_read != report.buffer.len() {
+            return Err(HidError::IncompleteSendError {
+                sent: bytes_read,
+                all: report.buffer.len(),
+            });
+        }
 
-    if bytes_read > 0 && bytes_read != report.buffer.len() {
-        return Err(HidError::IncompleteSendError {
-            sent: bytes_read,
-            all: report.buffer.len(),
-        });
+        Ok(report)
     }
 
-    Ok(match bytes_read > 0 {
-        true => Some(report),
-        false => None,
-    })
-}
+    fn read_report_non_blocking(&mut self, report: Report) -> Result<Option<Report>, HidError> {
+        self.read_report_timeout(report, Duration::ZERO)
+    }
+
+    fn read_report_timeout(
+        &mut self,
+        mut report: Report,
+        timeout: Duration,
+    ) -> Result<Option<Report>, HidError> {
+        let usb_device = pipe_to_usb_device(&mut self.usb_pipes, report.pipe);
 
-pub fn read_report(
-    communication_data: &mut CommunicationData,
-    mut report: Report,
-) -> Result<Report, HidError> {
-    let usb_device = pipe_to_usb_device(&mut communication_data.usb_pipes, report.pipe);
+        // Timeouts are rounded up to full milliseconds, so short waits still block.
+        let timeout_ms = u64::try_from(timeout.as_micros().div_ceil(1000)).unwrap_or(u64::MAX);
 
-    let bytes_read = usb_device.file.read(report.buffer.as_mut_slice())?;
+        let poll_timeout =
+            PollTimeout::try_from(Duration::from_millis(timeout_ms)).unwrap_or(PollTimeout::MAX);
 
-    if bytes_read != report.buffer.len() {
-        return Err(HidError::IncompleteSendError {
-            sent: bytes_read,
-            all: report.buffer.len(),
-        });
+        if !is_readable(usb_device, poll_timeout)? {
+            return Ok(None);
+        }
+
+        let bytes_read = usb_device.file.read(report.buffer.as_mut_slice())?;
+
+        if bytes_read > 0 && bytes_read != report.buffer.len() {
+            return Err(HidError::IncompleteSendError {
+                sent: bytes_read,
+                all: report.buffer.len(),
+            });
+        }
+
+        Ok(match bytes_read > 0 {
+            true => Some(report),
+            false => None,
+        })
     }
+}
+
+fn is_readable(usb_device: &USBPipe, poll_timeout: PollTimeout) -> Result<bool, HidError> {
+    let mut poll_file_descriptors = [PollFd::new(usb_device.file.as_fd(), PollFlags::POLLIN)];
+
+    let ready_count =
+        poll(&mut poll_file_descriptors, poll_timeout).map_err(|x| HidError::IoError {
+            error: std::io::Error::from(x),
+        })?;
 
-    Ok(report)
+    Ok(ready_count > 0)
 }
 
 fn pipe_to_usb_device(usb_pipes: &mut USBPipes, pipe: Pipe) -> &mut USBPipe {
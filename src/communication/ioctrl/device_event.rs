@@ -0,0 +1,14 @@
+use crate::iowarrior::IOWarriorType;
+use std::fmt;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum DeviceEvent {
+    Arrived(String, IOWarriorType),
+    Removed(String),
+}
+
+impl fmt::Display for DeviceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
@@ -0,0 +1,280 @@
+use crate::communication::ioctrl::DeviceEvent;
+use crate::communication::{sysfs_service, InitializationError};
+use crate::iowarrior::IOWarriorType;
+use hidapi::HidError;
+use nix::errno::Errno;
+use nix::poll::{poll, PollFd, PollFlags, PollTimeout};
+use nix::sys::inotify::{AddWatchFlags, InitFlags, Inotify, WatchDescriptor};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::os::fd::AsFd;
+use std::time::{Duration, Instant};
+use std::{fmt, fs};
+
+const DEVICE_DIRECTORY: &str = "/dev";
+const USB_DEVICE_DIRECTORY_NAME: &str = "usb";
+const USB_DEVICE_DIRECTORY: &str = "/dev/usb";
+const DEVICE_NAME_PREFIX: &str = "iowarrior";
+const PROBE_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+pub struct DeviceMonitor {
+    inotify: Inotify,
+    device_watch: WatchDescriptor,
+    usb_device_watch: Option<WatchDescriptor>,
+    pending_devices: HashMap<String, Instant>,
+    known_devices: HashMap<String, (String, IOWarriorType)>,
+    arrived_serials: HashSet<String>,
+    events: VecDeque<DeviceEvent>,
+}
+
+impl fmt::Display for DeviceMonitor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl DeviceMonitor {
+    pub fn new() -> Result<DeviceMonitor, InitializationError> {
+        let inotify = Inotify::init(InitFlags::IN_CLOEXEC | InitFlags::IN_NONBLOCK)
+            .map_err(to_initialization_error)?;
+
+        // /dev/usb only exists while at least one USB class device is attached.
+        let device_watch = inotify
+            .add_watch(
+                DEVICE_DIRECTORY,
+                AddWatchFlags::IN_CREATE | AddWatchFlags::IN_ONLYDIR,
+            )
+            .map_err(to_initialization_error)?;
+
+        let mut device_monitor = DeviceMonitor {
+            inotify,
+            device_watch,
+            usb_device_watch: None,
+            pending_devices: HashMap::new(),
+            known_devices: HashMap::new(),
+            arrived_serials: HashSet::new(),
+            events: VecDeque::new(),
+        };
+
+        device_monitor.watch_usb_device_directory()?;
+        device_monitor.probe_pending_devices();
+
+        Ok(device_monitor)
+    }
+
+    pub fn next_event(&mut self) -> Result<DeviceEvent, InitializationError> {
+        loop {
+            if let Some(event) = self.wait_for_event(None)? {
+                return Ok(event);
+            }
+        }
+    }
+
+    pub fn next_event_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<DeviceEvent>, InitializationError> {
+        self.wait_for_event(Some(Instant::now() + timeout))
+    }
+
+    fn wait_for_event(
+        &mut self,
+        deadline: Option<Instant>,
+    ) -> Result<Option<DeviceEvent>, InitializationError> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Ok(Some(event));
+            }
+
+            let mut wait_duration = deadline.map(|x| x.saturating_duration_since(Instant::now()));
+
+            if !self.pending_devices.is_empty() {
+                wait_duration = Some(match wait_duration {
+                    None => PROBE_RETRY_INTERVAL,
+                    Some(x) => Duration::min(x, PROBE_RETRY_INTERVAL),
+                });
+            }
+
+            let poll_timeout = match wait_duration {
+                None => PollTimeout::NONE,
+                Some(x) => PollTimeout::try_from(x).unwrap_or(PollTimeout::MAX),
+            };
+
+            let ready_count = {
+                let mut poll_file_descriptors =
+                    [PollFd::new(self.inotify.as_fd(), PollFlags::POLLIN)];
+
+                match poll(&mut poll_file_descriptors, poll_timeout) {
+                    Ok(x) => x,
+                    Err(Errno::EINTR) => 0,
+                    Err(x) => return Err(to_initialization_error(x)),
+                }
+            };
+
+            if ready_count > 0 {
+                self.handle_inotify_events()?;
+            }
+
+            self.probe_pending_devices();
+
+            if self.events.is_empty() && deadline.is_some_and(|x| Instant::now() >= x) {
+                return Ok(None);
+            }
+        }
+    }
+
+    fn handle_inotify_events(&mut self) -> Result<(), InitializationError> {
+        let inotify_events = match self.inotify.read_events() {
+            Ok(x) => x,
+            Err(Errno::EAGAIN) => return Ok(()),
+            Err(x) => return Err(to_initialization_error(x)),
+        };
+
+        for inotify_event in inotify_events {
+            if inotify_event.mask.contains(AddWatchFlags::IN_IGNORED)
+                && Some(inotify_event.wd) == self.usb_device_watch
+            {
+                self.usb_device_watch = None;
+                continue;
+            }
+
+            let name = match inotify_event.name.as_ref().and_then(|x| x.to_str()) {
+                None => continue,
+                Some(x) => x.to_owned(),
+            };
+
+            if inotify_event.wd == self.device_watch {
+                if name == USB_DEVICE_DIRECTORY_NAME {
+                    self.watch_usb_device_directory()?;
+                }
+            } else if Some(inotify_event.wd) == self.usb_device_watch
+                && name.starts_with(DEVICE_NAME_PREFIX)
+            {
+                match inotify_event.mask.contains(AddWatchFlags::IN_DELETE) {
+                    true => self.remove_device(&name),
+                    false => self.add_pending_device(name),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn watch_usb_device_directory(&mut self) -> Result<(), InitializationError> {
+        if self.usb_device_watch.is_some() {
+            return Ok(());
+        }
+
+        match self.inotify.add_watch(
+            USB_DEVICE_DIRECTORY,
+            AddWatchFlags::IN_CREATE
+                | AddWatchFlags::IN_DELETE
+                | AddWatchFlags::IN_ATTRIB
+                | AddWatchFlags::IN_ONLYDIR,
+        ) {
+            Ok(x) => self.usb_device_watch = Some(x),
+            Err(Errno::ENOENT) => return Ok(()),
+            Err(x) => return Err(to_initialization_error(x)),
+        }
+
+        // Devices created before the watch was added do not raise an event.
+        if let Ok(entries) = fs::read_dir(USB_DEVICE_DIRECTORY) {
+            for entry in entries.flatten() {
+                if let Some(name) = entry.file_name().to_str() {
+                    if name.starts_with(DEVICE_NAME_PREFIX) {
+                        self.add_pending_device(name.to_owned());
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_pending_device(&mut self, device_name: String) {
+        if !self.known_devices.contains_key(&device_name) {
+            self.pending_devices
+                .entry(device_name)
+                .or_insert_with(Instant::now);
+        }
+    }
+
+    fn remove_device(&mut self, device_name: &str) {
+        self.pending_devices.remove(device_name);
+
+        if let Some((device_serial, _)) = self.known_devices.remove(device_name) {
+            if self.arrived_serials.remove(&device_serial) {
+                self.events.push_back(DeviceEvent::Removed(device_serial));
+            }
+        }
+    }
+
+    fn probe_pending_devices(&mut self) {
+        let now = Instant::now();
+
+        let pending_devices: Vec<(String, Instant)> = self
+            .pending_devices
+            .iter()
+            .map(|(name, first_seen)| (name.clone(), *first_seen))
+            .collect();
+
+        for (device_name, first_seen) in pending_devices {
+            match sysfs_service::get_device_info(&device_name) {
+                None => {
+                    if now.duration_since(first_seen) >= PROBE_TIMEOUT {
+                        self.pending_devices.remove(&device_name);
+                    }
+                }
+                Some(device_info) => {
+                    self.pending_devices.remove(&device_name);
+
+                    if let Some(device_type) =
+                        IOWarriorType::from_device_product_id(device_info.device_product_id)
+                    {
+                        self.known_devices
+                            .insert(device_name, (device_info.device_serial, device_type));
+                    }
+                }
+            }
+        }
+
+        let mut interface_counts: HashMap<&String, (IOWarriorType, usize)> = HashMap::new();
+
+        for (device_serial, device_type) in self.known_devices.values() {
+            interface_counts
+                .entry(device_serial)
+                .or_insert((*device_type, 0))
+                .1 += 1;
+        }
+
+        for (device_serial, (device_type, interface_count)) in interface_counts {
+            if interface_count >= get_interface_count(device_type)
+                && self.arrived_serials.insert(device_serial.clone())
+            {
+                self.events
+                    .push_back(DeviceEvent::Arrived(device_serial.clone(), device_type));
+            }
+        }
+    }
+}
+
+fn get_interface_count(device_type: IOWarriorType) -> usize {
+    match device_type {
+        IOWarriorType::IOWarrior28
+        | IOWarriorType::IOWarrior28Dongle
+        | IOWarriorType::IOWarrior100 => 4,
+        IOWarriorType::IOWarrior40
+        | IOWarriorType::IOWarrior24
+        | IOWarriorType::IOWarrior24PowerVampire
+        | IOWarriorType::IOWarrior28L
+        | IOWarriorType::IOWarrior56
+        | IOWarriorType::IOWarrior56Dongle => 2,
+    }
+}
+
+fn to_initialization_error(errno: Errno) -> InitializationError {
+    InitializationError::ErrorUSB(HidError::IoError {
+        error: std::io::Error::from(errno),
+    })
+}
@@ -1,4 +1,7 @@
-use crate::communication::{CommunicationData, InitializationError, USBPipe, USBPipes};
+use crate::communication::ioctrl::{IoctrlTransport, USBPipe, USBPipes};
+use crate::communication::{
+    sysfs_service, Backend, IOWarriorDescriptor, InitializationError, SysfsDeviceInfo,
+};
 use crate::iowarrior::{iowarrior_service, IOWarrior, IOWarriorType};
 use hidapi::HidError::IoError;
 use itertools::Itertools;
@@ -34,6 +37,7 @@ impl fmt::Display for IoctlInfo {
 #[derive(Debug)]
 struct IOWarriorInfo {
     usb_pipe: USBPipe,
+    device_path: String,
     device_type: IOWarriorType,
     device_revision: u16,
     device_serial: String,
@@ -46,7 +50,12 @@ impl fmt::Display for IOWarriorInfo {
 }
 
 pub fn get_iowarriors() -> Result<Vec<IOWarrior>, InitializationError> {
-    let device_list = get_device_list()?;
+    let device_paths: Vec<String> = get_device_path_list()?
+        .into_iter()
+        .map(|(device_path, _)| device_path)
+        .collect();
+
+    let device_list = get_device_list(&device_paths)?;
 
     let grouped_usb_devices = device_list
         .into_iter()
@@ -64,9 +73,39 @@ pub fn get_iowarriors() -> Result<Vec<IOWarrior>, InitializationError> {
 }
 
 pub fn get_iowarrior(serial_number: &str) -> Result<IOWarrior, InitializationError> {
-    let device_list: Vec<IOWarriorInfo> = get_device_list()?;
+    open_iowarrior(serial_number, None)
+}
+
+// Only the nodes of the requested board are opened, nodes of other boards may be in use. Nodes
+// without sysfs information are opened to read their serial number.
+pub fn open_iowarrior(
+    serial_number: &str,
+    device_path: Option<&str>,
+) -> Result<IOWarrior, InitializationError> {
+    let device_path_list = get_device_path_list()?;
+
+    let is_listed = |x: &str| {
+        device_path_list.iter().any(|(path, device_info)| {
+            path == x
+                && device_info
+                    .as_ref()
+                    .is_none_or(|y| y.device_serial == serial_number)
+        })
+    };
+
+    if let Some(x) = device_path {
+        if !is_listed(x) {
+            return Err(InitializationError::NotFound(String::from(serial_number)));
+        }
+    }
 
-    let grouped_usb_device: Vec<_> = device_list
+    let device_paths: Vec<String> = device_path_list
+        .iter()
+        .filter(|(path, _)| is_listed(path))
+        .map(|(path, _)| path.clone())
+        .collect();
+
+    let grouped_usb_device: Vec<_> = get_device_list(&device_paths)?
         .into_iter()
         .filter(|iowarrior_info| iowarrior_info.device_serial == serial_number)
         .collect();
@@ -78,6 +117,38 @@ pub fn get_iowarrior(serial_number: &str) -> Result<IOWarrior, InitializationErr
     get_iowarrior_internal(grouped_usb_device, serial_number)
 }
 
+pub fn list_iowarriors() -> Result<Vec<IOWarriorDescriptor>, InitializationError> {
+    let mut vec: Vec<IOWarriorDescriptor> = Vec::new();
+
+    for (device_path, device_info) in get_device_path_list()? {
+        let device_info = match device_info {
+            None => continue,
+            Some(x) => x,
+        };
+
+        let device_type = match IOWarriorType::from_device_product_id(device_info.device_product_id)
+        {
+            None => continue,
+            Some(x) => x,
+        };
+
+        if device_info.interface != 0 {
+            continue;
+        }
+
+        vec.push(IOWarriorDescriptor {
+            device_serial: device_info.device_serial,
+            device_product_id: device_info.device_product_id,
+            device_type,
+            device_revision: device_info.device_revision,
+            device_path: Some(device_path),
+            backend: Backend::Ioctrl,
+        });
+    }
+
+    Ok(vec)
+}
+
 fn get_iowarrior_internal(
     device_infos: Vec<IOWarriorInfo>,
     serial_number: &str,
@@ -88,17 +159,26 @@ fn get_iowarrior_internal(
     let device_revision = iowarrior_info.device_revision.clone();
     let device_serial = iowarrior_info.device_serial.clone();
 
+    let device_path = device_infos
+        .iter()
+        .find(|x| x.usb_pipe.interface == 0)
+        .map(|x| x.device_path.clone());
+
     let usb_pipes = get_usb_pipes(device_type, device_infos)?;
 
-    let communication_data = CommunicationData { usb_pipes };
+    let transport = IoctrlTransport { usb_pipes };
 
-    iowarrior_service::create_iowarrior(
+    let iowarrior = iowarrior_service::create_iowarrior(
         device_type,
         device_revision,
         device_serial,
-        communication_data,
+        Box::new(transport),
     )
-    .map_err(|x| InitializationError::ErrorUSB(x))
+    .map_err(|x| InitializationError::ErrorUSB(x))?;
+
+    iowarrior_service::set_device_origin(&iowarrior, Backend::Ioctrl, device_path);
+
+    Ok(iowarrior)
 }
 
 fn get_usb_pipes(
@@ -147,9 +227,73 @@ fn get_usb_pipes(
     })
 }
 
-fn get_device_list() -> Result<Vec<IOWarriorInfo>, InitializationError> {
+fn get_device_list(device_paths: &[String]) -> Result<Vec<IOWarriorInfo>, InitializationError> {
     let mut device_list: Vec<IOWarriorInfo> = Vec::new();
 
+    for device_path in device_paths {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(device_path)
+            .map_err(|x| InitializationError::ErrorUSB(IoError { error: x }))?;
+
+        let raw_file_descriptor = file.as_raw_fd();
+
+        let mut ioctl_info = IoctlInfo {
+            vendor: 0,
+            product: 0,
+            serial: [0; 9],
+            revision: 0,
+            speed: 0,
+            power: 0,
+            interface: 0,
+            packet_size: 0,
+        };
+
+        match unsafe { ioctl_info_iowarrior(raw_file_descriptor, &mut ioctl_info) } {
+            Ok(_) => {}
+            Err(_) => {
+                return Err(InitializationError::InternalError(
+                    "Error getting device list.".to_owned(),
+                ))
+            }
+        }
+
+        if ioctl_info.vendor != VENDOR_IDENTIFIER {
+            continue;
+        }
+
+        let device_serial = get_serial_number(&ioctl_info)?;
+
+        if device_serial.is_empty() {
+            continue;
+        }
+
+        let device_type = match IOWarriorType::from_device_product_id(ioctl_info.product as u16) {
+            None => continue,
+            Some(x) => x,
+        };
+
+        let usb_pipe = USBPipe {
+            file,
+            interface: ioctl_info.interface as u8,
+        };
+
+        device_list.push(IOWarriorInfo {
+            device_path: device_path.clone(),
+            device_revision: ioctl_info.revision as u16,
+            device_serial,
+            device_type,
+            usb_pipe,
+        });
+    }
+
+    Ok(device_list)
+}
+
+fn get_device_path_list() -> Result<Vec<(String, Option<SysfsDeviceInfo>)>, InitializationError> {
+    let mut device_path_list: Vec<(String, Option<SysfsDeviceInfo>)> = Vec::new();
+
     for glob_result in glob::glob("/dev/usb/iowarrior*")
         .map_err(|x| InitializationError::InternalError("Error getting device list.".to_owned()))?
     {
@@ -157,73 +301,24 @@ fn get_device_list() -> Result<Vec<IOWarriorInfo>, InitializationError> {
             InitializationError::InternalError("Error getting device list.".to_owned())
         })?;
 
-        match entry.to_str() {
-            None => {
+        let device_name = entry.file_name().and_then(|x| x.to_str());
+
+        match (entry.to_str(), device_name) {
+            (Some(device_path), Some(device_name)) => {
+                device_path_list.push((
+                    device_path.to_owned(),
+                    sysfs_service::get_device_info(device_name),
+                ));
+            }
+            _ => {
                 return Err(InitializationError::InternalError(
                     "Error getting device list.".to_owned(),
                 ))
             }
-            Some(device_path) => {
-                let file = OpenOptions::new()
-                    .read(true)
-                    .write(true)
-                    .open(device_path)
-                    .map_err(|x| InitializationError::ErrorUSB(IoError { error: x }))?;
-
-                let raw_file_descriptor = file.as_raw_fd();
-
-                let mut ioctl_info = IoctlInfo {
-                    vendor: 0,
-                    product: 0,
-                    serial: [0; 9],
-                    revision: 0,
-                    speed: 0,
-                    power: 0,
-                    interface: 0,
-                    packet_size: 0,
-                };
-
-                match unsafe { ioctl_info_iowarrior(raw_file_descriptor, &mut ioctl_info) } {
-                    Ok(_) => {}
-                    Err(_) => {
-                        return Err(InitializationError::InternalError(
-                            "Error getting device list.".to_owned(),
-                        ))
-                    }
-                }
-
-                if ioctl_info.vendor != VENDOR_IDENTIFIER {
-                    continue;
-                }
-
-                let device_serial = get_serial_number(&ioctl_info)?;
-
-                if device_serial.is_empty() {
-                    continue;
-                }
-
-                let device_type =
-                    match IOWarriorType::from_device_product_id(ioctl_info.product as u16) {
-                        None => continue,
-                        Some(x) => x,
-                    };
-
-                let usb_pipe = USBPipe {
-                    file,
-                    interface: ioctl_info.interface as u8,
-                };
-
-                device_list.push(IOWarriorInfo {
-                    device_revision: ioctl_info.revision as u16,
-                    device_serial,
-                    device_type,
-                    usb_pipe,
-                });
-            }
         }
     }
 
-    Ok(device_list)
+    Ok(device_path_list)
 }
 
 fn get_serial_number(ioctl_info: &IoctlInfo) -> Result<String, InitializationError> {
@@ -1,8 +1,13 @@
 mod communication_data;
-pub(crate) mod communication_service;
-pub(crate) mod initialization_service;
+mod communication_service;
+mod device_event;
+mod device_monitor;
+mod initialization_service;
 
 pub(crate) use self::communication_data::*;
+pub use self::device_event::*;
+pub use self::device_monitor::*;
+pub use self::initialization_service::*;
 
 #[cfg(not(target_os = "linux"))]
 compile_error!("ioctrl backend only available on Linux.");
@@ -0,0 +1,19 @@
+use crate::communication::Backend;
+use crate::iowarrior::IOWarriorType;
+use std::fmt;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct IOWarriorDescriptor {
+    pub device_serial: String,
+    pub device_product_id: u16,
+    pub device_type: IOWarriorType,
+    pub device_revision: u16,
+    pub device_path: Option<String>,
+    pub backend: Backend,
+}
+
+impl fmt::Display for IOWarriorDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
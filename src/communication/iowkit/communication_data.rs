@@ -1,7 +1,15 @@
 use std::fmt;
 use std::os::raw;
 use std::ptr::NonNull;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+static IOWKIT_LOCK: Mutex<()> = Mutex::new(());
+
+// The iowkit documentation makes no thread safety guarantee, so every call into the library
+// holds this lock and the library is never entered from two threads at once.
+pub fn lock_iowkit() -> MutexGuard<'static, ()> {
+    IOWKIT_LOCK.lock().unwrap_or_else(PoisonError::into_inner)
+}
 
 #[derive(Debug)]
 pub struct IowkitData {
@@ -15,16 +23,28 @@ impl fmt::Display for IowkitData {
     }
 }
 
+// Handles are only passed to the library while holding IOWKIT_LOCK.
+#[cfg(feature = "sync")]
+unsafe impl Send for IowkitData {}
+
+#[cfg(feature = "sync")]
+unsafe impl Sync for IowkitData {}
+
 impl Drop for IowkitData {
     #[inline]
     fn drop(&mut self) {
+        let _lock = lock_iowkit();
+
         unsafe { self.iowkit.IowKitCloseDevice(self.iowkit_handle.as_ptr()) }
     }
 }
 
 #[derive(Debug)]
-pub struct CommunicationData {
+pub struct IowkitTransport {
     pub iowkit_data: Arc<IowkitData>,
     pub device_handle: NonNull<raw::c_void>,
     pub max_pipe: u8,
 }
+
+#[cfg(feature = "sync")]
+unsafe impl Send for IowkitTransport {}
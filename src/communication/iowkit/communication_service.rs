@@ -1,75 +1,137 @@
-use crate::communication::CommunicationData;
+use crate::communication::iowkit::{lock_iowkit, IowkitTransport};
+use crate::communication::Transport;
 use crate::iowarrior::Report;
 use hidapi::HidError;
+use std::time::{Duration, Instant};
 
-pub fn write_report(
-    communication_data: &mut CommunicationData,
-    report: &Report,
-) -> Result<(), HidError> {
-    let pipe = u8::min(report.pipe.get_value(), communication_data.max_pipe);
+const INFINITE_TIMEOUT: u32 = 0xFFFFFFFF;
 
-    let written_bytes = unsafe {
-        communication_data.iowkit_data.iowkit.IowKitWrite(
-            communication_data.device_handle.as_ptr(),
-            pipe as iowkit_sys::ULONG,
-            report.buffer.as_ptr() as iowkit_sys::PCHAR,
-            report.buffer.len() as iowkit_sys::ULONG,
-        )
-    } as usize;
-
-    if written_bytes != report.buffer.len() {
-        return Err(HidError::IncompleteSendError {
-            sent: written_bytes,
-            all: report.buffer.len(),
-        });
+// Blocking reads are split into slices, so a waiting board does not keep the iowkit lock.
+const READ_SLICE: Duration = Duration::from_millis(100);
+
+impl Transport for IowkitTransport {
+    fn write_report(&mut self, report: &Report) -> Result<(), HidError> {
+        let pipe = u8::min(report.pipe.get_value(), self.max_pipe);
+
+        let written_bytes = {
+            let _lock = lock_iowkit();
+
+            unsafe {
+                self.iowkit_data.iowkit.IowKitWrite(
+                    self.device_handle.as_ptr(),
+                    pipe as iowkit_sys::ULONG,
+                    report.buffer.as_ptr() as iowkit_sys::PCHAR,
+                    report.buffer.len() as iowkit_sys::ULONG,
+                )
+            }
+        } as usize;
+
+        if written_bytes != report.buffer.len() {
+            return Err(HidError::IncompleteSendError {
+                sent: written_bytes,
+                all: report.buffer.len(),
+            });
+        }
+
+        Ok(())
     }
 
-    Ok(())
-}
+    fn read_report(&mut self, mut report: Report) -> Result<Report, HidError> {
+        loop {
+            let start = Instant::now();
 
-pub fn read_report_non_blocking(
-    communication_data: &mut CommunicationData,
-    mut report: Report,
-) -> Result<Option<Report>, HidError> {
-    let pipe = u8::min(report.pipe.get_value(), communication_data.max_pipe);
+            let read_bytes = read_timeout(self, &mut report, READ_SLICE);
 
-    let read_bytes = unsafe {
-        communication_data.iowkit_data.iowkit.IowKitReadNonBlocking(
-            communication_data.device_handle.as_ptr(),
-            pipe as iowkit_sys::ULONG,
-            report.buffer.as_mut_ptr() as iowkit_sys::PCHAR,
-            report.buffer.len() as iowkit_sys::ULONG,
-        )
-    } as usize;
+            if read_bytes == report.buffer.len() {
+                return Ok(report);
+            }
+
+            // A read failing before its timeout elapsed means the device is gone.
+            if read_bytes != 0 || start.elapsed() < READ_SLICE / 2 {
+                return Err(HidError::IncompleteSendError {
+                    sent: read_bytes,
+                    all: report.buffer.len(),
+                });
+            }
+        }
+    }
+
+    fn read_report_non_blocking(&mut self, mut report: Report) -> Result<Option<Report>, HidError> {
+        let pipe = u8::min(report.pipe.get_value(), self.max_pipe);
 
-    if read_bytes != report.buffer.len() {
-        return Ok(None);
+        let read_bytes = {
+            let _lock = lock_iowkit();
+
+            unsafe {
+                self.iowkit_data.iowkit.IowKitReadNonBlocking(
+                    self.device_handle.as_ptr(),
+                    pipe as iowkit_sys::ULONG,
+                    report.buffer.as_mut_ptr() as iowkit_sys::PCHAR,
+                    report.buffer.len() as iowkit_sys::ULONG,
+                )
+            }
+        } as usize;
+
+        if read_bytes != report.buffer.len() {
+            return Ok(None);
+        }
+
+        Ok(Some(report))
     }
 
-    Ok(Some(report))
+    fn read_report_timeout(
+        &mut self,
+        mut report: Report,
+        timeout: Duration,
+    ) -> Result<Option<Report>, HidError> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+
+            let read_bytes = read_timeout(self, &mut report, Duration::min(remaining, READ_SLICE));
+
+            if read_bytes == report.buffer.len() {
+                return Ok(Some(report));
+            }
+
+            if Instant::now() >= deadline {
+                return Ok(None);
+            }
+        }
+    }
 }
 
-pub fn read_report(
-    communication_data: &mut CommunicationData,
-    mut report: Report,
-) -> Result<Report, HidError> {
-    let pipe = u8::min(report.pipe.get_value(), communication_data.max_pipe);
+fn read_timeout(transport: &IowkitTransport, report: &mut Report, timeout: Duration) -> usize {
+    let pipe = u8::min(report.pipe.get_value(), transport.max_pipe);
+
+    // Timeouts are rounded up to full milliseconds, a timeout of zero would not wait at all.
+    let timeout_ms = u32::try_from(timeout.as_micros().div_ceil(1000))
+        .unwrap_or(INFINITE_TIMEOUT - 1)
+        .max(1);
+
+    let _lock = lock_iowkit();
+
+    (unsafe {
+        let iowkit = &transport.iowkit_data.iowkit;
 
-    let read_bytes = unsafe {
-        communication_data.iowkit_data.iowkit.IowKitRead(
-            communication_data.device_handle.as_ptr(),
+        iowkit.IowKitSetTimeout(
+            transport.device_handle.as_ptr(),
+            timeout_ms as iowkit_sys::ULONG,
+        );
+
+        let read_bytes = iowkit.IowKitRead(
+            transport.device_handle.as_ptr(),
             pipe as iowkit_sys::ULONG,
             report.buffer.as_mut_ptr() as iowkit_sys::PCHAR,
             report.buffer.len() as iowkit_sys::ULONG,
-        )
-    } as usize;
-
-    if read_bytes != report.buffer.len() {
-        return Err(HidError::IncompleteSendError {
-            sent: read_bytes,
-            all: report.buffer.len(),
-        });
-    }
+        );
+
+        iowkit.IowKitSetTimeout(
+            transport.device_handle.as_ptr(),
+            INFINITE_TIMEOUT as iowkit_sys::ULONG,
+        );
 
-    Ok(report)
+        read_bytes
+    }) as usize
 }
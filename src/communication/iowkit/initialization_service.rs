@@ -1,8 +1,15 @@
-use crate::communication::IowkitData;
-use crate::communication::{CommunicationData, InitializationError};
+use crate::communication::iowkit::{lock_iowkit, IowkitData, IowkitTransport};
+#[cfg(target_os = "linux")]
+use crate::communication::sysfs_service;
+use crate::communication::{Backend, IOWarriorDescriptor, InitializationError};
 use crate::iowarrior::{iowarrior_service, IOWarrior, IOWarriorType, Pipe};
+#[cfg(target_os = "windows")]
+use hidapi::HidApi;
+#[cfg(target_os = "linux")]
+use std::fs;
+use std::os::raw;
 use std::ptr::NonNull;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, PoisonError, Weak};
 
 #[cfg(target_os = "windows")]
 const IOWKIT: &str = "iowkit.dll";
@@ -10,39 +17,113 @@ const IOWKIT: &str = "iowkit.dll";
 #[cfg(target_os = "linux")]
 const IOWKIT: &str = "libiowkit.so";
 
+#[cfg(target_os = "windows")]
+const VENDOR_IDENTIFIER: u16 = 1984;
+
+// IowKitOpenDevice opens every board and IowKitCloseDevice closes all of them again, so all boards
+// of the process share one session. A new session is only opened once every board is dropped.
+static IOWKIT_SESSION: Mutex<IowkitSession> = Mutex::new(IowkitSession(Weak::new()));
+
+struct IowkitSession(Weak<IowkitData>);
+
+// The session is only upgraded while holding IOWKIT_SESSION, the library calls hold IOWKIT_LOCK.
+unsafe impl Send for IowkitSession {}
+
 pub fn get_iowarriors() -> Result<Vec<IOWarrior>, InitializationError> {
+    open_iowarriors(None)
+}
+
+pub fn get_iowarrior(serial_number: &str) -> Result<IOWarrior, InitializationError> {
+    open_iowarrior(serial_number, None)
+}
+
+// iowkit has no device paths, the board is found by its serial number.
+pub fn open_iowarrior(
+    serial_number: &str,
+    _device_path: Option<&str>,
+) -> Result<IOWarrior, InitializationError> {
+    match open_iowarriors(Some(serial_number))?.pop() {
+        None => Err(InitializationError::NotFound(String::from(serial_number))),
+        Some(x) => Ok(x),
+    }
+}
+
+fn get_session() -> Result<Option<Arc<IowkitData>>, InitializationError> {
+    let mut session = IOWKIT_SESSION
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+
+    if let Some(x) = session.0.upgrade() {
+        return Ok(Some(x));
+    }
+
     let iowkit = unsafe { iowkit_sys::Iowkit::new(IOWKIT) }.map_err(|x| {
         InitializationError::InternalError("Error loading iowkit library.".to_owned())
     })?;
 
-    let iowkit_handle = match NonNull::new(unsafe { iowkit.IowKitOpenDevice() }) {
-        None => return Ok(Vec::<IOWarrior>::with_capacity(0)),
-        Some(x) => x,
-    };
+    let iowkit_handle = {
+        let _lock = lock_iowkit();
 
-    let device_count = unsafe { iowkit.IowKitGetNumDevs() };
-    let mut vec: Vec<IOWarrior> = Vec::new();
+        match NonNull::new(unsafe { iowkit.IowKitOpenDevice() }) {
+            None => return Ok(None),
+            Some(x) => x,
+        }
+    };
 
     let iowkit_data = Arc::new(IowkitData {
         iowkit,
         iowkit_handle,
     });
 
+    session.0 = Arc::downgrade(&iowkit_data);
+
+    Ok(Some(iowkit_data))
+}
+
+// Boards of another serial number are skipped before they are probed.
+fn open_iowarriors(serial_number: Option<&str>) -> Result<Vec<IOWarrior>, InitializationError> {
+    let iowkit_data = match get_session()? {
+        None => return Ok(Vec::<IOWarrior>::with_capacity(0)),
+        Some(x) => x,
+    };
+
+    let device_count = {
+        let _lock = lock_iowkit();
+
+        unsafe { iowkit_data.iowkit.IowKitGetNumDevs() }
+    };
+
+    let mut vec: Vec<IOWarrior> = Vec::new();
+
     for index in 0..device_count {
-        let device_handle =
-            match NonNull::new(unsafe { iowkit_data.iowkit.IowKitGetDeviceHandle(index + 1) }) {
+        let (device_handle, device_product_id, device_revision, device_serial) = {
+            let _lock = lock_iowkit();
+
+            let device_handle = match NonNull::new(unsafe {
+                iowkit_data.iowkit.IowKitGetDeviceHandle(index + 1)
+            }) {
                 None => continue,
                 Some(x) => x,
             };
 
-        let device_product_id = unsafe {
-            iowkit_data
-                .iowkit
-                .IowKitGetProductId(device_handle.as_ptr())
-        } as u16;
+            let device_product_id = unsafe {
+                iowkit_data
+                    .iowkit
+                    .IowKitGetProductId(device_handle.as_ptr())
+            } as u16;
+
+            let device_revision =
+                unsafe { iowkit_data.iowkit.IowKitGetRevision(device_handle.as_ptr()) } as u16;
 
-        let device_revision =
-            unsafe { iowkit_data.iowkit.IowKitGetRevision(device_handle.as_ptr()) } as u16;
+            let device_serial = get_serial_number(&iowkit_data, device_handle);
+
+            (
+                device_handle,
+                device_product_id,
+                device_revision,
+                device_serial,
+            )
+        };
 
         let device_type = match IOWarriorType::from_device_product_id(device_product_id) {
             None => continue,
@@ -53,26 +134,15 @@ pub fn get_iowarriors() -> Result<Vec<IOWarrior>, InitializationError> {
             continue;
         }
 
-        let device_serial = {
-            let mut raw_device_serial_number = [0u16; 9];
-
-            let device_serial_number_result = unsafe {
-                iowkit_data.iowkit.IowKitGetSerialNumber(
-                    device_handle.as_ptr(),
-                    raw_device_serial_number.as_mut_ptr(),
-                )
-            };
-
-            if device_serial_number_result > 0i32 {
-                String::from_utf16_lossy(&raw_device_serial_number)
-            } else {
-                return Err(InitializationError::InternalError(
-                    "Failed to get serial number.".to_owned(),
-                ));
+        if let Some(x) = serial_number {
+            if device_serial.as_deref().ok() != Some(x) {
+                continue;
             }
-        };
+        }
+
+        let device_serial = device_serial?;
 
-        let communication_data = CommunicationData {
+        let transport = IowkitTransport {
             iowkit_data: iowkit_data.clone(),
             device_handle,
             max_pipe: get_max_pipe(device_type),
@@ -82,16 +152,127 @@ pub fn get_iowarriors() -> Result<Vec<IOWarrior>, InitializationError> {
             device_type,
             device_revision,
             device_serial,
-            communication_data,
+            Box::new(transport),
         )
         .map_err(|x| InitializationError::ErrorUSB(x))?;
 
+        iowarrior_service::set_device_origin(&iowarrior, Backend::Iowkit, None);
+
         vec.push(iowarrior);
     }
 
     Ok(vec)
 }
 
+// The iowkit library can only enumerate by opening all devices, and closing them again would also close
+// the handles of boards already in use. The boards are therefore listed from the operating system.
+#[cfg(target_os = "windows")]
+pub fn list_iowarriors() -> Result<Vec<IOWarriorDescriptor>, InitializationError> {
+    let api = HidApi::new().map_err(InitializationError::ErrorUSB)?;
+
+    let mut vec: Vec<IOWarriorDescriptor> = Vec::new();
+
+    for device_info in api.device_list().filter(|x| {
+        x.vendor_id() == VENDOR_IDENTIFIER
+            && x.serial_number().is_some()
+            && x.interface_number() == 0
+    }) {
+        let device_type = match IOWarriorType::from_device_product_id(device_info.product_id()) {
+            None => continue,
+            Some(x) => x,
+        };
+
+        if device_type == IOWarriorType::IOWarrior40 && device_info.release_number() < 0x1010 {
+            continue;
+        }
+
+        vec.push(IOWarriorDescriptor {
+            device_serial: String::from(device_info.serial_number().unwrap()),
+            device_product_id: device_info.product_id(),
+            device_type,
+            device_revision: device_info.release_number(),
+            device_path: None,
+            backend: Backend::Iowkit,
+        });
+    }
+
+    Ok(vec)
+}
+
+#[cfg(target_os = "linux")]
+pub fn list_iowarriors() -> Result<Vec<IOWarriorDescriptor>, InitializationError> {
+    let mut vec: Vec<IOWarriorDescriptor> = Vec::new();
+
+    let entries = match fs::read_dir("/sys/class/usbmisc") {
+        Err(_) => return Ok(vec),
+        Ok(x) => x,
+    };
+
+    for entry in entries {
+        let entry = entry.map_err(|x| {
+            InitializationError::InternalError("Error getting device list.".to_owned())
+        })?;
+
+        let device_name = entry.file_name();
+
+        let device_info = match device_name
+            .to_str()
+            .filter(|x| x.starts_with("iowarrior"))
+            .and_then(sysfs_service::get_device_info)
+        {
+            None => continue,
+            Some(x) => x,
+        };
+
+        let device_type = match IOWarriorType::from_device_product_id(device_info.device_product_id)
+        {
+            None => continue,
+            Some(x) => x,
+        };
+
+        if device_info.interface != 0 {
+            continue;
+        }
+
+        if device_type == IOWarriorType::IOWarrior40 && device_info.device_revision < 0x1010 {
+            continue;
+        }
+
+        vec.push(IOWarriorDescriptor {
+            device_serial: device_info.device_serial,
+            device_product_id: device_info.device_product_id,
+            device_type,
+            device_revision: device_info.device_revision,
+            device_path: None,
+            backend: Backend::Iowkit,
+        });
+    }
+
+    Ok(vec)
+}
+
+fn get_serial_number(
+    iowkit_data: &IowkitData,
+    device_handle: NonNull<raw::c_void>,
+) -> Result<String, InitializationError> {
+    let mut raw_device_serial_number = [0u16; 9];
+
+    let device_serial_number_result = unsafe {
+        iowkit_data.iowkit.IowKitGetSerialNumber(
+            device_handle.as_ptr(),
+            raw_device_serial_number.as_mut_ptr(),
+        )
+    };
+
+    if device_serial_number_result > 0i32 {
+        Ok(String::from_utf16_lossy(&raw_device_serial_number))
+    } else {
+        Err(InitializationError::InternalError(
+            "Failed to get serial number.".to_owned(),
+        ))
+    }
+}
+
 fn get_max_pipe(device_type: IOWarriorType) -> u8 {
     match device_type {
         IOWarriorType::IOWarrior28
@@ -105,14 +286,3 @@ fn get_max_pipe(device_type: IOWarriorType) -> u8 {
         | IOWarriorType::IOWarrior56Dongle => Pipe::SpecialMode.get_value(),
     }
 }
-
-pub fn get_iowarrior(serial_number: &str) -> Result<IOWarrior, InitializationError> {
-    match get_iowarriors()?
-        .into_iter()
-        .filter(|x| x.get_serial_number() == serial_number)
-        .next()
-    {
-        None => Err(InitializationError::NotFound(String::from(serial_number))),
-        Some(x) => Ok(x),
-    }
-}
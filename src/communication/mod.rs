@@ -1,17 +1,31 @@
+mod backend;
+mod communication_data;
+pub(crate) mod communication_service;
+mod disconnected_transport;
 mod initialization_error;
+pub(crate) mod initialization_service;
 #[cfg(feature = "ioctrl")]
-mod ioctrl;
+pub mod ioctrl;
+mod iowarrior_descriptor;
 #[cfg(feature = "iowkit")]
-mod iowkit;
+pub mod iowkit;
+mod reconnect_data;
+#[cfg(feature = "simulator")]
+pub mod simulator;
+#[cfg(all(target_os = "linux", any(feature = "ioctrl", feature = "iowkit")))]
+mod sysfs_device_info;
+#[cfg(all(target_os = "linux", any(feature = "ioctrl", feature = "iowkit")))]
+pub(crate) mod sysfs_service;
+mod transport;
 #[cfg(feature = "usbhid")]
-mod usbhid;
-pub use self::initialization_error::*;
-#[cfg(feature = "ioctrl")]
-pub(crate) use self::ioctrl::*;
-#[cfg(feature = "iowkit")]
-pub(crate) use self::iowkit::*;
-#[cfg(feature = "usbhid")]
-pub(crate) use self::usbhid::*;
+pub mod usbhid;
 
-#[cfg(not(any(feature = "iowkit", feature = "usbhid", feature = "ioctrl")))]
-compile_error!("No backend selected. Enable one of the following features: iowkit, usbhid, ioctrl");
\ No newline at end of file
+pub use self::backend::*;
+pub(crate) use self::communication_data::*;
+pub(crate) use self::disconnected_transport::*;
+pub use self::initialization_error::*;
+pub use self::iowarrior_descriptor::*;
+pub(crate) use self::reconnect_data::*;
+#[cfg(all(target_os = "linux", any(feature = "ioctrl", feature = "iowkit")))]
+pub(crate) use self::sysfs_device_info::*;
+pub use self::transport::*;
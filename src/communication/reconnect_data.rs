@@ -0,0 +1,16 @@
+use crate::iowarrior::IOWarriorType;
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReconnectData {
+    pub device_serial: String,
+    pub device_type: IOWarriorType,
+    pub timeout: Duration,
+}
+
+impl fmt::Display for ReconnectData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
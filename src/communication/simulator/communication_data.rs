@@ -0,0 +1,101 @@
+use crate::iowarrior::IOWarriorType;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug)]
+pub struct SimulatedI2CMemory {
+    pub pointer: u8,
+    pub cells: [u8; 256],
+}
+
+impl Default for SimulatedI2CMemory {
+    fn default() -> Self {
+        SimulatedI2CMemory {
+            pointer: 0,
+            cells: [0xFF; 256],
+        }
+    }
+}
+
+impl fmt::Display for SimulatedI2CMemory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum SimulatedI2CAddress {
+    SevenBit(u8),
+    TenBit(u16),
+}
+
+impl fmt::Display for SimulatedI2CAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SimulatedI2C {
+    pub enabled: bool,
+    pub clock: u8,
+    pub selected_address: Option<SimulatedI2CAddress>,
+    pub pointer_pending: bool,
+    pub stalled: bool,
+    pub held_reports: Vec<Vec<u8>>,
+    pub memories: HashMap<SimulatedI2CAddress, SimulatedI2CMemory>,
+}
+
+impl fmt::Display for SimulatedI2C {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SimulatedADC {
+    pub resolution_bits: u8,
+    pub packet_number: u8,
+}
+
+impl fmt::Display for SimulatedADC {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Debug)]
+pub struct SimulatedBoard {
+    pub device_serial: String,
+    pub device_type: IOWarriorType,
+    pub device_revision: u16,
+    pub connected: bool,
+    pub connection_number: u32,
+    pub pins_latch: Vec<u8>,
+    pub pins_pulled_low: Vec<u8>,
+    pub pending_reports: [VecDeque<Vec<u8>>; 4],
+    pub i2c: SimulatedI2C,
+    pub spi_enabled: bool,
+    pub pwm_enabled: bool,
+    pub ir_enabled: bool,
+    pub adc: Option<SimulatedADC>,
+}
+
+impl fmt::Display for SimulatedBoard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Debug)]
+pub struct SimulatorTransport {
+    pub board: Arc<Mutex<SimulatedBoard>>,
+    pub connection_number: u32,
+}
+
+impl fmt::Display for SimulatorTransport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
@@ -0,0 +1,444 @@
+use crate::bits::Bit::{Bit6, Bit7};
+use crate::bits::{Bit, Bitmasking};
+use crate::communication::simulator::{
+    SimulatedADC, SimulatedBoard, SimulatedI2CAddress, SimulatorTransport,
+};
+use crate::communication::Transport;
+use crate::iowarrior::{iowarrior_service, IOWarriorType, Pipe, Report, ReportId};
+use crate::ir::IRFrame;
+use embedded_hal::digital::PinState;
+use hidapi::HidError;
+use std::io::{self, ErrorKind};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+const MAX_PENDING_REPORTS: usize = 64;
+
+impl Transport for SimulatorTransport {
+    fn write_report(&mut self, report: &Report) -> Result<(), HidError> {
+        let mut board = lock_board(&self.board);
+
+        check_connection(&board, self.connection_number)?;
+
+        write_report(&mut board, report)
+    }
+
+    fn read_report(&mut self, report: Report) -> Result<Report, HidError> {
+        match self.read_report_non_blocking(report)? {
+            Some(report) => Ok(report),
+            None => Err(HidError::HidApiError {
+                message: "Simulated IOWarrior has no pending report, read would block forever."
+                    .to_owned(),
+            }),
+        }
+    }
+
+    fn read_report_non_blocking(&mut self, report: Report) -> Result<Option<Report>, HidError> {
+        let mut board = lock_board(&self.board);
+
+        check_connection(&board, self.connection_number)?;
+
+        read_report_non_blocking(&mut board, report)
+    }
+}
+
+pub fn lock_board(board: &Mutex<SimulatedBoard>) -> MutexGuard<'_, SimulatedBoard> {
+    board.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn write_report(board: &mut SimulatedBoard, report: &Report) -> Result<(), HidError> {
+    if report.buffer.is_empty() {
+        return Err(HidError::InvalidZeroSizeData);
+    }
+
+    match report.pipe {
+        Pipe::IOPins => write_pins(board, report),
+        Pipe::SpecialMode | Pipe::I2CMode | Pipe::ADCMode => write_special_mode(board, report),
+    }
+}
+
+fn read_report_non_blocking(
+    board: &mut SimulatedBoard,
+    mut report: Report,
+) -> Result<Option<Report>, HidError> {
+    let interface = get_interface(board.device_type, report.pipe);
+
+    let buffer = match board.pending_reports[interface].pop_front() {
+        Some(buffer) => Some(buffer),
+        None => match report.pipe {
+            Pipe::ADCMode => create_adc_report(board, report.buffer.len()),
+            Pipe::IOPins | Pipe::SpecialMode | Pipe::I2CMode => None,
+        },
+    };
+
+    Ok(buffer.map(|buffer| {
+        for (to, from) in report.buffer.iter_mut().zip(buffer) {
+            *to = from;
+        }
+
+        report
+    }))
+}
+
+// Handles opened before the board was unplugged stay disconnected, like a real device node.
+fn check_connection(board: &SimulatedBoard, connection_number: u32) -> Result<(), HidError> {
+    match board.connected && board.connection_number == connection_number {
+        true => Ok(()),
+        false => Err(HidError::IoError {
+            error: io::Error::from(ErrorKind::NotConnected),
+        }),
+    }
+}
+
+// A board that is plugged in again starts with its power on state, the I2C memories keep their content.
+pub fn set_connected(board: &mut SimulatedBoard, connected: bool) {
+    board.connected = connected;
+
+    if connected {
+        board.connection_number = board.connection_number.wrapping_add(1);
+    }
+
+    board.pins_latch = vec![0xFF; board.pins_latch.len()];
+    board.pending_reports = Default::default();
+    board.i2c.enabled = false;
+    board.i2c.selected_address = None;
+    board.i2c.pointer_pending = false;
+    board.i2c.stalled = false;
+    board.i2c.held_reports.clear();
+    board.spi_enabled = false;
+    board.pwm_enabled = false;
+    board.ir_enabled = false;
+    board.adc = None;
+}
+
+fn get_interface(device_type: IOWarriorType, pipe: Pipe) -> usize {
+    match device_type {
+        IOWarriorType::IOWarrior28
+        | IOWarriorType::IOWarrior28Dongle
+        | IOWarriorType::IOWarrior100 => pipe.get_value() as usize,
+        IOWarriorType::IOWarrior40
+        | IOWarriorType::IOWarrior24
+        | IOWarriorType::IOWarrior24PowerVampire
+        | IOWarriorType::IOWarrior28L
+        | IOWarriorType::IOWarrior56
+        | IOWarriorType::IOWarrior56Dongle => match pipe {
+            Pipe::IOPins => 0,
+            Pipe::SpecialMode | Pipe::I2CMode | Pipe::ADCMode => 1,
+        },
+    }
+}
+
+fn push_report(board: &mut SimulatedBoard, pipe: Pipe, buffer: Vec<u8>) {
+    let interface = get_interface(board.device_type, pipe);
+    let pending_reports = &mut board.pending_reports[interface];
+
+    if pending_reports.len() >= MAX_PENDING_REPORTS {
+        pending_reports.pop_front();
+    }
+
+    pending_reports.push_back(buffer);
+}
+
+fn write_pins(board: &mut SimulatedBoard, report: &Report) -> Result<(), HidError> {
+    let pins_latch: Vec<u8> = report.buffer.iter().skip(1).copied().collect();
+
+    update_pins(board, |board| board.pins_latch = pins_latch);
+
+    Ok(())
+}
+
+pub fn set_pin_input_state(board: &mut SimulatedBoard, pin: u8, pin_state: PinState) {
+    update_pins(board, |board| {
+        if let Some(x) = board.pins_pulled_low.get_mut((pin / 8) as usize) {
+            x.set_bit(Bit::from_pin(pin), pin_state == PinState::Low);
+        }
+    });
+}
+
+// Quasi-bidirectional pins are pulled up, so every pin follows its latch unless it is pulled low
+// from outside. Every change of the pin states is reported.
+fn update_pins(board: &mut SimulatedBoard, f: impl FnOnce(&mut SimulatedBoard)) {
+    let previous_pin_states = get_pin_states(board);
+
+    f(board);
+
+    let pin_states = get_pin_states(board);
+
+    if pin_states != previous_pin_states {
+        let mut buffer = vec![ReportId::GpioReadWrite.get_value()];
+
+        buffer.extend(pin_states);
+
+        push_report(board, Pipe::IOPins, buffer);
+    }
+}
+
+fn get_pin_states(board: &SimulatedBoard) -> Vec<u8> {
+    board
+        .pins_latch
+        .iter()
+        .zip(board.pins_pulled_low.iter())
+        .map(|(latch, pulled_low)| latch & !pulled_low)
+        .collect()
+}
+
+fn write_special_mode(board: &mut SimulatedBoard, report: &Report) -> Result<(), HidError> {
+    let report_id = match ReportId::from_u8(report.buffer[0]) {
+        None => return Ok(()),
+        Some(x) => x,
+    };
+
+    match report_id {
+        ReportId::GpioSpecialRead => {
+            let mut buffer = vec![0u8; report.buffer.len()];
+
+            buffer[0] = ReportId::GpioSpecialRead.get_value();
+
+            for (to, from) in buffer.iter_mut().skip(1).zip(get_pin_states(board)) {
+                *to = from;
+            }
+
+            push_report(board, report.pipe, buffer);
+        }
+        ReportId::I2cSetup => {
+            board.i2c.enabled = report.buffer[1] == 0x01;
+            board.i2c.clock = report.buffer[2];
+            board.i2c.selected_address = None;
+            board.i2c.held_reports.clear();
+        }
+        ReportId::I2cWrite => write_i2c(board, report),
+        ReportId::I2cRead => read_i2c(board, report),
+        ReportId::SpiSetup => {
+            board.spi_enabled = report.buffer[1] == 0x01;
+        }
+        ReportId::SpiTransfer => transfer_spi(board, report),
+        ReportId::PwmSetup => {
+            board.pwm_enabled = report.buffer[1] > 0x00;
+        }
+        ReportId::AdcSetup => match board.device_type {
+            IOWarriorType::IOWarrior28Dongle | IOWarriorType::IOWarrior56Dongle => {
+                return Err(HidError::IncompleteSendError {
+                    sent: 0,
+                    all: report.buffer.len(),
+                });
+            }
+            IOWarriorType::IOWarrior40
+            | IOWarriorType::IOWarrior24
+            | IOWarriorType::IOWarrior24PowerVampire
+            | IOWarriorType::IOWarrior28
+            | IOWarriorType::IOWarrior28L
+            | IOWarriorType::IOWarrior56
+            | IOWarriorType::IOWarrior100 => {
+                board.adc = match report.buffer[1] == 0x01 {
+                    true => Some(SimulatedADC {
+                        resolution_bits: match board.device_type {
+                            IOWarriorType::IOWarrior56 => 14,
+                            _ => 12,
+                        },
+                        packet_number: 0,
+                    }),
+                    false => None,
+                };
+            }
+        },
+        ReportId::IrSetup => {
+            board.ir_enabled = report.buffer[1] == 0x01;
+        }
+        ReportId::AdcRead
+        | ReportId::IrRead
+        | ReportId::PwmParameters
+        | ReportId::TimerSetup
+        | ReportId::TimerDataA
+        | ReportId::TimerDataB
+        | ReportId::GpioReadWrite => {}
+    }
+
+    Ok(())
+}
+
+fn write_i2c(board: &mut SimulatedBoard, report: &Report) {
+    if !board.i2c.enabled {
+        return;
+    }
+
+    let flags = report.buffer[1];
+    let count = usize::min((flags & 0x3F) as usize, report.buffer.len() - 2);
+
+    let i2c = &mut board.i2c;
+    let mut bytes = report.buffer[2..(count + 2)].iter();
+
+    let mut buffer = vec![0u8; report.buffer.len()];
+
+    buffer[0] = ReportId::I2cWrite.get_value();
+
+    if flags.get_bit(Bit7) {
+        i2c.selected_address = match bytes.next() {
+            None => None,
+            Some(x) if x & 0xF8 == 0xF0 => bytes
+                .next()
+                .map(|y| SimulatedI2CAddress::TenBit(((*x as u16 & 0x06) << 7) | *y as u16)),
+            Some(x) => Some(SimulatedI2CAddress::SevenBit(x >> 1)),
+        };
+        i2c.pointer_pending = true;
+
+        // Only attached memories acknowledge their address.
+        if let Some(address) = i2c.selected_address {
+            if !i2c.memories.contains_key(&address) {
+                i2c.selected_address = None;
+                buffer[1].set_bit(Bit7, true);
+            }
+        }
+    }
+
+    if let Some(memory) = i2c
+        .selected_address
+        .and_then(|address| i2c.memories.get_mut(&address))
+    {
+        for byte in bytes {
+            if i2c.pointer_pending {
+                memory.pointer = *byte;
+                i2c.pointer_pending = false;
+            } else {
+                memory.cells[memory.pointer as usize] = *byte;
+                memory.pointer = memory.pointer.wrapping_add(1);
+            }
+        }
+    }
+
+    if flags.get_bit(Bit6) {
+        i2c.selected_address = None;
+    }
+
+    push_i2c_response(board, report.pipe, buffer);
+}
+
+fn read_i2c(board: &mut SimulatedBoard, report: &Report) {
+    if !board.i2c.enabled {
+        return;
+    }
+
+    let count = usize::min((report.buffer[1] & 0x3F) as usize, report.buffer.len() - 2);
+    let header = report.buffer[2];
+
+    let mut buffer = vec![0u8; report.buffer.len()];
+
+    buffer[0] = ReportId::I2cRead.get_value();
+
+    // A 10 bit read header only addresses the slave selected by the preceding write.
+    let address = match (header & 0xF8 == 0xF0, board.i2c.selected_address) {
+        (false, _) => Some(SimulatedI2CAddress::SevenBit(header >> 1)),
+        (true, Some(SimulatedI2CAddress::TenBit(x))) if (x >> 7) as u8 & 0x06 == header & 0x06 => {
+            Some(SimulatedI2CAddress::TenBit(x))
+        }
+        (true, _) => None,
+    };
+
+    let memory = match address.and_then(|address| board.i2c.memories.get_mut(&address)) {
+        None => {
+            buffer[1].set_bit(Bit7, true);
+
+            push_i2c_response(board, report.pipe, buffer);
+
+            return;
+        }
+        Some(x) => x,
+    };
+
+    buffer[1] = count as u8;
+
+    for to in buffer.iter_mut().skip(2).take(count) {
+        *to = memory.cells[memory.pointer as usize];
+        memory.pointer = memory.pointer.wrapping_add(1);
+    }
+
+    board.i2c.selected_address = None;
+
+    push_i2c_response(board, report.pipe, buffer);
+}
+
+// A stalled bus holds the responses back until it is released or I2C is disabled.
+fn push_i2c_response(board: &mut SimulatedBoard, pipe: Pipe, buffer: Vec<u8>) {
+    match board.i2c.stalled {
+        true => board.i2c.held_reports.push(buffer),
+        false => push_report(board, pipe, buffer),
+    }
+}
+
+pub fn attach_i2c_memory(board: &mut SimulatedBoard, address: SimulatedI2CAddress) {
+    board.i2c.memories.entry(address).or_default();
+}
+
+// The IOWarrior56 clock or IOWarrior100 speed value of the last setup report.
+pub fn get_i2c_clock(board: &SimulatedBoard) -> Option<u8> {
+    match board.i2c.enabled {
+        true => Some(board.i2c.clock),
+        false => None,
+    }
+}
+
+pub fn set_i2c_stalled(board: &mut SimulatedBoard, stalled: bool) {
+    board.i2c.stalled = stalled;
+
+    if !stalled {
+        for buffer in std::mem::take(&mut board.i2c.held_reports) {
+            push_report(board, Pipe::I2CMode, buffer);
+        }
+    }
+}
+
+fn transfer_spi(board: &mut SimulatedBoard, report: &Report) {
+    if !board.spi_enabled {
+        return;
+    }
+
+    let (count, data_offset) = match board.device_type {
+        IOWarriorType::IOWarrior56 | IOWarriorType::IOWarrior56Dongle => {
+            (report.buffer[1] as usize, 3usize)
+        }
+        _ => ((report.buffer[1] & 0x3F) as usize, 2usize),
+    };
+
+    let count = usize::min(count, report.buffer.len() - data_offset);
+
+    let mut buffer = vec![0u8; report.buffer.len()];
+
+    buffer[0] = ReportId::SpiTransfer.get_value();
+    buffer[1] = count as u8;
+
+    // MISO is looped back to MOSI.
+    buffer[2..(count + 2)].copy_from_slice(&report.buffer[data_offset..(count + data_offset)]);
+
+    push_report(board, report.pipe, buffer);
+}
+
+fn create_adc_report(board: &mut SimulatedBoard, size: usize) -> Option<Vec<u8>> {
+    let adc = board.adc.as_mut()?;
+
+    let mut buffer = vec![0u8; size];
+
+    buffer[0] = ReportId::AdcRead.get_value();
+    buffer[1] = adc.packet_number;
+
+    let sample = (1u16 << (adc.resolution_bits - 1)).to_le_bytes();
+
+    for chunk in buffer.chunks_exact_mut(2).skip(1) {
+        chunk.copy_from_slice(&sample);
+    }
+
+    adc.packet_number = adc.packet_number.wrapping_add(1);
+
+    Some(buffer)
+}
+
+pub fn receive_ir_frame(board: &mut SimulatedBoard, ir_frame: &IRFrame) {
+    if !board.ir_enabled {
+        return;
+    }
+
+    let mut buffer = vec![0u8; iowarrior_service::get_special_report_size(board.device_type)];
+
+    buffer[0] = ReportId::IrRead.get_value();
+    buffer[1] = (ir_frame.address & 0x1F) | ((ir_frame.toggle as u8) << 5);
+    buffer[2] = ir_frame.command & 0x3F;
+
+    push_report(board, Pipe::SpecialMode, buffer);
+}
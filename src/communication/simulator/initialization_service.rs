@@ -0,0 +1,133 @@
+use crate::communication::simulator::{
+    communication_service, SimulatedBoard, SimulatedI2C, SimulatorControl, SimulatorTransport,
+};
+use crate::communication::{Backend, IOWarriorDescriptor, InitializationError};
+use crate::iowarrior::{iowarrior_service, IOWarrior, IOWarriorType};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, PoisonError, Weak};
+
+static NEXT_SERIAL_NUMBER: AtomicU32 = AtomicU32::new(1);
+
+static SIMULATED_BOARDS: Mutex<Vec<(String, Weak<Mutex<SimulatedBoard>>)>> = Mutex::new(Vec::new());
+
+pub fn get_iowarriors() -> Result<Vec<IOWarrior>, InitializationError> {
+    Ok(Vec::new())
+}
+
+pub fn list_iowarriors() -> Result<Vec<IOWarriorDescriptor>, InitializationError> {
+    Ok(Vec::new())
+}
+
+// Opens another handle to a connected simulated board, which still has to be alive.
+pub fn get_iowarrior(serial_number: &str) -> Result<IOWarrior, InitializationError> {
+    let board = match get_simulated_board(serial_number) {
+        None => return Err(InitializationError::NotFound(String::from(serial_number))),
+        Some(x) => x,
+    };
+
+    if !communication_service::lock_board(&board).connected {
+        return Err(InitializationError::NotFound(String::from(serial_number)));
+    }
+
+    create_simulated_iowarrior(board)
+}
+
+pub fn open_iowarrior(
+    serial_number: &str,
+    _device_path: Option<&str>,
+) -> Result<IOWarrior, InitializationError> {
+    get_iowarrior(serial_number)
+}
+
+pub fn get_simulated_iowarrior(
+    device_type: IOWarriorType,
+    device_revision: u16,
+) -> Result<IOWarrior, InitializationError> {
+    let device_serial = format!("{:08X}", NEXT_SERIAL_NUMBER.fetch_add(1, Ordering::Relaxed));
+
+    let board = Arc::new(Mutex::new(SimulatedBoard {
+        device_serial: device_serial.clone(),
+        device_type,
+        device_revision,
+        connected: true,
+        connection_number: 0,
+        pins_latch: vec![0xFF; iowarrior_service::get_standard_report_size(device_type) - 1],
+        pins_pulled_low: vec![0x00; iowarrior_service::get_standard_report_size(device_type) - 1],
+        pending_reports: Default::default(),
+        i2c: SimulatedI2C::default(),
+        spi_enabled: false,
+        pwm_enabled: false,
+        ir_enabled: false,
+        adc: None,
+    }));
+
+    {
+        let mut simulated_boards = SIMULATED_BOARDS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        simulated_boards.retain(|(_, x)| x.strong_count() > 0);
+        simulated_boards.push((device_serial.clone(), Arc::downgrade(&board)));
+    }
+
+    create_simulated_iowarrior(board)
+}
+
+pub fn get_simulator_control(serial_number: &str) -> Option<SimulatorControl> {
+    get_simulated_board(serial_number).map(|board| SimulatorControl { board })
+}
+
+fn get_simulated_board(serial_number: &str) -> Option<Arc<Mutex<SimulatedBoard>>> {
+    SIMULATED_BOARDS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .filter(|(device_serial, _)| device_serial == serial_number)
+        .find_map(|(_, x)| x.upgrade())
+}
+
+fn create_simulated_iowarrior(
+    board: Arc<Mutex<SimulatedBoard>>,
+) -> Result<IOWarrior, InitializationError> {
+    let (device_type, device_revision, device_serial, connection_number) = {
+        let board = communication_service::lock_board(&board);
+
+        (
+            board.device_type,
+            board.device_revision,
+            board.device_serial.clone(),
+            board.connection_number,
+        )
+    };
+
+    let transport = SimulatorTransport {
+        board,
+        connection_number,
+    };
+
+    let iowarrior = iowarrior_service::create_iowarrior(
+        get_product_type(device_type),
+        device_revision,
+        device_serial,
+        Box::new(transport),
+    )
+    .map_err(InitializationError::ErrorUSB)?;
+
+    iowarrior_service::set_device_origin(&iowarrior, Backend::Simulator, None);
+
+    Ok(iowarrior)
+}
+
+fn get_product_type(device_type: IOWarriorType) -> IOWarriorType {
+    match device_type {
+        IOWarriorType::IOWarrior28Dongle => IOWarriorType::IOWarrior28,
+        IOWarriorType::IOWarrior56Dongle => IOWarriorType::IOWarrior56,
+        IOWarriorType::IOWarrior40
+        | IOWarriorType::IOWarrior24
+        | IOWarriorType::IOWarrior24PowerVampire
+        | IOWarriorType::IOWarrior28
+        | IOWarriorType::IOWarrior28L
+        | IOWarriorType::IOWarrior56
+        | IOWarriorType::IOWarrior100 => device_type,
+    }
+}
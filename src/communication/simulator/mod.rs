@@ -0,0 +1,8 @@
+mod communication_data;
+mod communication_service;
+mod initialization_service;
+mod simulator_control;
+
+pub(crate) use self::communication_data::*;
+pub use self::initialization_service::*;
+pub use self::simulator_control::*;
@@ -0,0 +1,88 @@
+use crate::communication::simulator::{communication_service, SimulatedBoard, SimulatedI2CAddress};
+use crate::ir::IRFrame;
+use embedded_hal::digital::PinState;
+use embedded_hal::i2c::{SevenBitAddress, TenBitAddress};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Debug)]
+pub struct SimulatorControl {
+    pub(crate) board: Arc<Mutex<SimulatedBoard>>,
+}
+
+impl fmt::Display for SimulatorControl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl SimulatorControl {
+    #[inline]
+    pub fn get_serial_number(&self) -> String {
+        communication_service::lock_board(&self.board)
+            .device_serial
+            .clone()
+    }
+
+    #[inline]
+    pub fn disconnect(&self) {
+        communication_service::set_connected(
+            &mut communication_service::lock_board(&self.board),
+            false,
+        );
+    }
+
+    #[inline]
+    pub fn connect(&self) {
+        communication_service::set_connected(
+            &mut communication_service::lock_board(&self.board),
+            true,
+        );
+    }
+
+    #[inline]
+    pub fn set_pin_input_state(&self, pin: u8, pin_state: PinState) {
+        communication_service::set_pin_input_state(
+            &mut communication_service::lock_board(&self.board),
+            pin,
+            pin_state,
+        );
+    }
+
+    #[inline]
+    pub fn attach_i2c_memory(&self, address: SevenBitAddress) {
+        communication_service::attach_i2c_memory(
+            &mut communication_service::lock_board(&self.board),
+            SimulatedI2CAddress::SevenBit(address),
+        );
+    }
+
+    #[inline]
+    pub fn attach_ten_bit_i2c_memory(&self, address: TenBitAddress) {
+        communication_service::attach_i2c_memory(
+            &mut communication_service::lock_board(&self.board),
+            SimulatedI2CAddress::TenBit(address),
+        );
+    }
+
+    #[inline]
+    pub fn get_i2c_clock(&self) -> Option<u8> {
+        communication_service::get_i2c_clock(&communication_service::lock_board(&self.board))
+    }
+
+    #[inline]
+    pub fn set_i2c_stalled(&self, stalled: bool) {
+        communication_service::set_i2c_stalled(
+            &mut communication_service::lock_board(&self.board),
+            stalled,
+        );
+    }
+
+    #[inline]
+    pub fn receive_ir_frame(&self, ir_frame: IRFrame) {
+        communication_service::receive_ir_frame(
+            &mut communication_service::lock_board(&self.board),
+            &ir_frame,
+        );
+    }
+}
@@ -0,0 +1,15 @@
+use std::fmt;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SysfsDeviceInfo {
+    pub device_serial: String,
+    pub device_product_id: u16,
+    pub device_revision: u16,
+    pub interface: u8,
+}
+
+impl fmt::Display for SysfsDeviceInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
@@ -0,0 +1,43 @@
+use crate::communication::SysfsDeviceInfo;
+use std::fs;
+use std::path::Path;
+
+const VENDOR_IDENTIFIER: u16 = 1984;
+
+pub fn get_device_info(device_name: &str) -> Option<SysfsDeviceInfo> {
+    let interface_path = fs::canonicalize(
+        Path::new("/sys/class/usbmisc")
+            .join(device_name)
+            .join("device"),
+    )
+    .ok()?;
+
+    let usb_device_path = interface_path.parent()?;
+
+    if read_hex_attribute(usb_device_path, "idVendor")? != VENDOR_IDENTIFIER {
+        return None;
+    }
+
+    let device_serial = read_attribute(usb_device_path, "serial")?;
+
+    if device_serial.is_empty() {
+        return None;
+    }
+
+    Some(SysfsDeviceInfo {
+        device_serial,
+        device_product_id: read_hex_attribute(usb_device_path, "idProduct")?,
+        device_revision: read_hex_attribute(usb_device_path, "bcdDevice")?,
+        interface: read_hex_attribute(&interface_path, "bInterfaceNumber")? as u8,
+    })
+}
+
+fn read_attribute(path: &Path, attribute: &str) -> Option<String> {
+    fs::read_to_string(path.join(attribute))
+        .ok()
+        .map(|x| x.trim().to_owned())
+}
+
+fn read_hex_attribute(path: &Path, attribute: &str) -> Option<u16> {
+    u16::from_str_radix(&read_attribute(path, attribute)?, 16).ok()
+}
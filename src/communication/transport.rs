@@ -0,0 +1,50 @@
+use crate::iowarrior::Report;
+use hidapi::HidError;
+use std::fmt;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+#[cfg(feature = "sync")]
+pub trait MaybeSend: Send {}
+
+#[cfg(feature = "sync")]
+impl<T: Send> MaybeSend for T {}
+
+#[cfg(not(feature = "sync"))]
+pub trait MaybeSend {}
+
+#[cfg(not(feature = "sync"))]
+impl<T> MaybeSend for T {}
+
+pub trait Transport: fmt::Debug + MaybeSend {
+    fn write_report(&mut self, report: &Report) -> Result<(), HidError>;
+
+    fn read_report(&mut self, report: Report) -> Result<Report, HidError>;
+
+    fn read_report_non_blocking(&mut self, report: Report) -> Result<Option<Report>, HidError>;
+
+    // Polls until the timeout, backends with a blocking read with timeout override it.
+    fn read_report_timeout(
+        &mut self,
+        report: Report,
+        timeout: Duration,
+    ) -> Result<Option<Report>, HidError> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            if let Some(x) = self.read_report_non_blocking(report.clone())? {
+                return Ok(Some(x));
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+
+            if remaining.is_zero() {
+                return Ok(None);
+            }
+
+            thread::sleep(Duration::min(remaining, POLL_INTERVAL));
+        }
+    }
+}
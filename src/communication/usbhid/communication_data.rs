@@ -22,11 +22,11 @@ impl fmt::Display for USBPipes {
 }
 
 #[derive(Debug)]
-pub struct CommunicationData {
+pub struct UsbhidTransport {
     pub usb_pipes: USBPipes,
 }
 
-impl fmt::Display for CommunicationData {
+impl fmt::Display for UsbhidTransport {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:?}", self)
     }
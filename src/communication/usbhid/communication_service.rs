@@ -1,66 +1,86 @@
-use crate::communication::{CommunicationData, USBPipes};
+use crate::communication::usbhid::{USBPipes, UsbhidTransport};
+use crate::communication::Transport;
 use crate::iowarrior::{Pipe, Report};
 use hidapi::{HidDevice, HidError};
+use std::time::Duration;
 
-pub fn write_report(
-    communication_data: &mut CommunicationData,
-    report: &Report,
-) -> Result<(), HidError> {
-    let usb_device = pipe_to_hid_device(&communication_data.usb_pipes, report.pipe);
+impl Transport for UsbhidTransport {
+    fn write_report(&mut self, report: &Report) -> Result<(), HidError> {
+        let usb_device = pipe_to_hid_device(&self.usb_pipes, report.pipe);
 
-    let bytes_written = usb_device.write(report.buffer.as_slice())?;
+        let bytes_written = usb_device.write(report.buffer.as_slice())?;
 
-    if bytes_written != report.buffer.len() {
-        return Err(HidError::IncompleteSendError {
-            sent: bytes_written,
-            all: report.buffer.len(),
-        });
+        if bytes_written != report.buffer.len() {
+            return Err(HidError::IncompleteSendError {
+                sent: bytes_written,
+                all: report.buffer.len(),
+            });
+        }
+
+        Ok(())
     }
 
-    Ok(())
-}
+    fn read_report(&mut self, mut report: Report) -> Result<Report, HidError> {
+        let usb_device = pipe_to_hid_device(&self.usb_pipes, report.pipe);
 
-pub fn read_report_non_blocking(
-    communication_data: &mut CommunicationData,
-    mut report: Report,
-) -> Result<Option<Report>, HidError> {
-    let usb_device = pipe_to_hid_device(&communication_data.usb_pipes, report.pipe);
+        usb_device.set_blocking_mode(true)?;
 
-    usb_device.set_blocking_mode(false)?;
+        let bytes_read = usb_device.read(report.buffer.as_mut_slice())?;
 
-    let bytes_read = usb_device.read(report.buffer.as_mut_slice())?;
+        if bytes_read != report.buffer.len() {
+            return Err(HidError::IncompleteSendError {
+                sent: bytes_read,
+                all: report.buffer.len(),
+            });
+        }
 
-    if bytes_read > 0 && bytes_read != report.buffer.len() {
-        return Err(HidError::IncompleteSendError {
-            sent: bytes_read,
-            all: report.buffer.len(),
-        });
+        Ok(report)
     }
 
-    Ok(match bytes_read > 0 {
-        true => Some(report),
-        false => None,
-    })
-}
+    fn read_report_non_blocking(&mut self, mut report: Report) -> Result<Option<Report>, HidError> {
+        let usb_device = pipe_to_hid_device(&self.usb_pipes, report.pipe);
 
-pub fn read_report(
-    communication_data: &mut CommunicationData,
-    mut report: Report,
-) -> Result<Report, HidError> {
-    let usb_device = pipe_to_hid_device(&communication_data.usb_pipes, report.pipe);
+        usb_device.set_blocking_mode(false)?;
 
-    usb_device.set_blocking_mode(true)?;
+        let bytes_read = usb_device.read(report.buffer.as_mut_slice())?;
 
-    let bytes_read = usb_device.read(report.buffer.as_mut_slice())?;
+        if bytes_read > 0 && bytes_read != report.buffer.len() {
+            return Err(HidError::IncompleteSendError {
+                sent: bytes_read,
+                all: report.buffer.len(),
+            });
+        }
 
-    if bytes_read != report.buffer.len() {
-        return Err(HidError::IncompleteSendError {
-            sent: bytes_read,
-            all: report.buffer.len(),
-        });
+        Ok(match bytes_read > 0 {
+            true => Some(report),
+            false => None,
+        })
     }
 
-    Ok(report)
+    fn read_report_timeout(
+        &mut self,
+        mut report: Report,
+        timeout: Duration,
+    ) -> Result<Option<Report>, HidError> {
+        let usb_device = pipe_to_hid_device(&self.usb_pipes, report.pipe);
+
+        // Timeouts are rounded up to full milliseconds, so short waits still block.
+        let timeout_ms = i32::try_from(timeout.as_micros().div_ceil(1000)).unwrap_or(i32::MAX);
+
+        let bytes_read = usb_device.read_timeout(report.buffer.as_mut_slice(), timeout_ms)?;
+
+        if bytes_read > 0 && bytes_read != report.buffer.len() {
+            return Err(HidError::IncompleteSendError {
+                sent: bytes_read,
+                all: report.buffer.len(),
+            });
+        }
+
+        Ok(match bytes_read > 0 {
+            true => Some(report),
+            false => None,
+        })
+    }
 }
 
 fn pipe_to_hid_device(usb_pipes: &USBPipes, pipe: Pipe) -> &HidDevice {
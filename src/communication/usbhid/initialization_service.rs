@@ -1,4 +1,5 @@
-use crate::communication::{CommunicationData, InitializationError, USBPipes};
+use crate::communication::usbhid::{USBPipes, UsbhidTransport};
+use crate::communication::{Backend, IOWarriorDescriptor, InitializationError};
 use crate::iowarrior::{iowarrior_service, IOWarrior, IOWarriorType};
 use hidapi::HidError::IoError;
 use hidapi::{DeviceInfo, HidApi, HidDevice};
@@ -33,6 +34,14 @@ pub fn get_iowarriors() -> Result<Vec<IOWarrior>, InitializationError> {
 }
 
 pub fn get_iowarrior(serial_number: &str) -> Result<IOWarrior, InitializationError> {
+    open_iowarrior(serial_number, None)
+}
+
+// The device path is the path of the first interface, as listed by list_iowarriors.
+pub fn open_iowarrior(
+    serial_number: &str,
+    device_path: Option<&str>,
+) -> Result<IOWarrior, InitializationError> {
     let api = HidApi::new().map_err(|x| InitializationError::ErrorUSB(x))?;
 
     let grouped_usb_device: Vec<&DeviceInfo> = api
@@ -48,9 +57,46 @@ pub fn get_iowarrior(serial_number: &str) -> Result<IOWarrior, InitializationErr
         return Err(InitializationError::NotFound(String::from(serial_number)));
     }
 
+    if let Some(x) = device_path {
+        if !grouped_usb_device
+            .iter()
+            .any(|y| y.interface_number() == 0 && get_hid_path(y).ok() == Some(x))
+        {
+            return Err(InitializationError::NotFound(String::from(serial_number)));
+        }
+    }
+
     get_iowarrior_internal(&api, &grouped_usb_device, serial_number)
 }
 
+pub fn list_iowarriors() -> Result<Vec<IOWarriorDescriptor>, InitializationError> {
+    let api = HidApi::new().map_err(InitializationError::ErrorUSB)?;
+
+    let mut vec: Vec<IOWarriorDescriptor> = Vec::new();
+
+    for device_info in api.device_list().filter(|x| {
+        x.vendor_id() == VENDOR_IDENTIFIER
+            && x.serial_number().is_some()
+            && x.interface_number() == 0
+    }) {
+        let device_type = match IOWarriorType::from_device_product_id(device_info.product_id()) {
+            None => continue,
+            Some(x) => x,
+        };
+
+        vec.push(IOWarriorDescriptor {
+            device_serial: String::from(device_info.serial_number().unwrap()),
+            device_product_id: device_info.product_id(),
+            device_type,
+            device_revision: device_info.release_number(),
+            device_path: Some(String::from(get_hid_path(device_info)?)),
+            backend: Backend::Usbhid,
+        });
+    }
+
+    Ok(vec)
+}
+
 fn get_iowarrior_internal(
     api: &HidApi,
     device_infos: &Vec<&DeviceInfo>,
@@ -68,15 +114,23 @@ fn get_iowarrior_internal(
 
     let usb_pipes = open_hid_pipes(&api, device_type, &device_infos)?;
 
-    let communication_data = CommunicationData { usb_pipes };
+    let transport = UsbhidTransport { usb_pipes };
 
-    iowarrior_service::create_iowarrior(
+    let iowarrior = iowarrior_service::create_iowarrior(
         device_type,
         device_revision,
         String::from(serial_number),
-        communication_data,
+        Box::new(transport),
     )
-    .map_err(|x| InitializationError::ErrorUSB(x))
+    .map_err(|x| InitializationError::ErrorUSB(x))?;
+
+    iowarrior_service::set_device_origin(
+        &iowarrior,
+        Backend::Usbhid,
+        Some(String::from(pipe_0_path)),
+    );
+
+    Ok(iowarrior)
 }
 
 fn get_hid_path(device_info: &DeviceInfo) -> Result<&str, InitializationError> {
@@ -1,8 +1,9 @@
 mod communication_data;
-pub(crate) mod communication_service;
-pub(crate) mod initialization_service;
+mod communication_service;
+mod initialization_service;
 
 pub(crate) use self::communication_data::*;
+pub use self::initialization_service::*;
 
 #[cfg(not(target_os = "windows"))]
 compile_error!("usbhid backend only available on Windows.");
@@ -0,0 +1,21 @@
+use crate::delay::Delay;
+use crate::iowarrior::unblock;
+use std::thread;
+use std::time::Duration;
+
+impl embedded_hal_async::delay::DelayNs for Delay {
+    #[inline]
+    async fn delay_ns(&mut self, ns: u32) {
+        unblock(move |_| thread::sleep(Duration::from_nanos(ns as u64))).await
+    }
+
+    #[inline]
+    async fn delay_us(&mut self, us: u32) {
+        unblock(move |_| thread::sleep(Duration::from_micros(us as u64))).await
+    }
+
+    #[inline]
+    async fn delay_ms(&mut self, ms: u32) {
+        unblock(move |_| thread::sleep(Duration::from_millis(ms as u64))).await
+    }
+}
@@ -1,2 +1,4 @@
 mod delay;
+#[cfg(feature = "embedded-hal-async")]
+mod delay_async;
 pub use self::delay::*;
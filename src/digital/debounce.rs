@@ -0,0 +1,14 @@
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Debounce {
+    Duration(Duration),
+    Reports(usize),
+}
+
+impl fmt::Display for Debounce {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
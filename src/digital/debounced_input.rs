@@ -0,0 +1,159 @@
+use crate::digital::{digital_service, Debounce, InputPin, PinError, PinEvent};
+use embedded_hal::digital::PinState;
+use std::collections::VecDeque;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+const MAX_PENDING_EVENTS: usize = 64;
+
+const READ_SLICE: Duration = Duration::from_millis(10);
+
+#[derive(Debug)]
+pub struct DebouncedInput {
+    pub(crate) input_pin: InputPin,
+    pub(crate) debounce: Debounce,
+    pub(crate) pin_state: PinState,
+    pub(crate) candidate: Option<(PinState, Instant, usize)>,
+    pub(crate) events: VecDeque<PinEvent>,
+}
+
+impl embedded_hal::digital::ErrorType for DebouncedInput {
+    type Error = PinError;
+}
+
+impl embedded_hal::digital::InputPin for DebouncedInput {
+    #[inline]
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        self.update(Duration::ZERO)?;
+
+        Ok(self.pin_state == PinState::High)
+    }
+
+    #[inline]
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        self.update(Duration::ZERO)?;
+
+        Ok(self.pin_state == PinState::Low)
+    }
+}
+
+impl fmt::Display for DebouncedInput {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl DebouncedInput {
+    #[inline]
+    pub fn new(input_pin: InputPin, debounce: Debounce) -> DebouncedInput {
+        digital_service::into_debounced_input(input_pin, debounce)
+    }
+
+    #[inline]
+    pub fn get_debounce(&self) -> Debounce {
+        self.debounce
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> InputPin {
+        self.input_pin
+    }
+
+    #[inline]
+    pub fn try_next_edge(&mut self) -> Result<Option<PinEvent>, PinError> {
+        self.update(Duration::ZERO)?;
+
+        Ok(self.events.pop_front())
+    }
+
+    pub fn next_edge(&mut self, timeout: Option<Duration>) -> Result<PinEvent, PinError> {
+        let deadline = timeout.map(|x| Instant::now() + x);
+
+        loop {
+            if let Some(pin_event) = self.events.pop_front() {
+                return Ok(pin_event);
+            }
+
+            let now = Instant::now();
+
+            let mut wait_duration = READ_SLICE;
+
+            if let Some(deadline) = deadline {
+                if now >= deadline {
+                    return Err(PinError::Timeout);
+                }
+
+                wait_duration = Duration::min(wait_duration, deadline - now);
+            }
+
+            if let (Debounce::Duration(duration), Some((_, since, _))) =
+                (self.debounce, self.candidate)
+            {
+                wait_duration = Duration::min(
+                    wait_duration,
+                    (since + duration).saturating_duration_since(now),
+                );
+            }
+
+            self.update(wait_duration)?;
+        }
+    }
+
+    fn update(&mut self, timeout: Duration) -> Result<(), PinError> {
+        let (pin_states, latched_pin_state) = digital_service::read_pin_input_states(
+            &self.input_pin.data,
+            &mut self.input_pin.mut_data_refcell.borrow_mut(),
+            self.input_pin.pin,
+            timeout,
+        )?;
+
+        // Only IOPins reports count for Debounce::Reports, a duration also needs the latched state
+        // while no reports arrive.
+        let samples = match (pin_states.is_empty(), self.debounce) {
+            (true, Debounce::Duration(_)) => vec![latched_pin_state],
+            _ => pin_states,
+        };
+
+        for pin_state in samples {
+            let now = Instant::now();
+
+            self.candidate = match self.candidate {
+                _ if pin_state == self.pin_state => None,
+                Some((candidate, since, count)) if candidate == pin_state => {
+                    Some((candidate, since, count + 1))
+                }
+                _ => Some((pin_state, now, 1)),
+            };
+
+            self.check_candidate(now);
+        }
+
+        self.check_candidate(Instant::now());
+
+        Ok(())
+    }
+
+    fn check_candidate(&mut self, now: Instant) {
+        if let Some((pin_state, since, count)) = self.candidate {
+            let is_stable = match self.debounce {
+                Debounce::Duration(duration) => now.saturating_duration_since(since) >= duration,
+                Debounce::Reports(reports) => count >= reports,
+            };
+
+            if is_stable {
+                self.pin_state = pin_state;
+                self.candidate = None;
+
+                if self.events.len() >= MAX_PENDING_EVENTS {
+                    self.events.pop_front();
+                }
+
+                self.events.push_back(PinEvent {
+                    instant: now,
+                    pin: self.input_pin.pin,
+                    pin_state,
+                });
+            }
+        }
+    }
+}
@@ -1,120 +1,465 @@
 use crate::bits::Bit;
 use crate::bits::Bitmasking;
 use crate::communication::communication_service;
-use crate::digital::{InputPin, OutputPin, PinError, PinSetupError};
+use crate::digital::{
+    Debounce, DebouncedInput, FlexPin, GpioPort, InputPin, OutputPin, PinError, PinEvent,
+    PinSetupError, WaveformStatistics, WaveformStep,
+};
+#[cfg(feature = "sync")]
+use crate::digital::{PinMonitor, WaveformPlayer};
 use crate::iowarrior::{
-    peripheral_service, IOWarriorData, IOWarriorMutData, IOWarriorType, Pipe, UsedPin,
+    peripheral_service, IOWarriorData, IOWarriorMutData, IOWarriorType, Pipe, Report, ReportId,
+    Shared, SharedCell, SharedRefMut, UsedPin,
 };
 use embedded_hal::digital::PinState;
-use std::cell::{RefCell, RefMut};
-use std::rc::Rc;
+use std::collections::VecDeque;
+use std::sync::mpsc::Sender;
+#[cfg(feature = "sync")]
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc, Arc,
+};
+use std::time::{Duration, Instant};
+
+const WAIT_SLICE: Duration = Duration::from_millis(10);
+const SPIN_THRESHOLD: Duration = Duration::from_millis(2);
 
 pub fn new_input(
-    data: &Rc<IOWarriorData>,
-    mut_data_refcell: &Rc<RefCell<IOWarriorMutData>>,
+    data: &Shared<IOWarriorData>,
+    mut_data_refcell: &Shared<SharedCell<IOWarriorMutData>>,
     pin: u8,
 ) -> Result<InputPin, PinSetupError> {
     let mut mut_data = mut_data_refcell.borrow_mut();
 
-    enable_gpio(&data, &mut mut_data, PinState::High, pin)?;
+    enable_gpio(&data, &mut mut_data, &[(pin, PinState::High)])?;
 
     Ok(InputPin {
         pin,
         data: data.clone(),
         mut_data_refcell: mut_data_refcell.clone(),
+        is_converted: false,
     })
 }
 
 pub fn new_output(
-    data: &Rc<IOWarriorData>,
-    mut_data_refcell: &Rc<RefCell<IOWarriorMutData>>,
+    data: &Shared<IOWarriorData>,
+    mut_data_refcell: &Shared<SharedCell<IOWarriorMutData>>,
     pin_state: PinState,
     pin: u8,
 ) -> Result<OutputPin, PinSetupError> {
     let mut mut_data = mut_data_refcell.borrow_mut();
 
-    enable_gpio(&data, &mut mut_data, pin_state, pin)?;
+    enable_gpio(&data, &mut mut_data, &[(pin, pin_state)])?;
 
     Ok(OutputPin {
         pin,
         data: data.clone(),
         mut_data_refcell: mut_data_refcell.clone(),
+        is_converted: false,
     })
 }
 
-fn enable_gpio(
-    data: &IOWarriorData,
-    mut_data: &mut RefMut<IOWarriorMutData>,
+pub fn new_flex(
+    data: &Shared<IOWarriorData>,
+    mut_data_refcell: &Shared<SharedCell<IOWarriorMutData>>,
     pin_state: PinState,
     pin: u8,
-) -> Result<(), PinSetupError> {
+) -> Result<FlexPin, PinSetupError> {
+    let mut mut_data = mut_data_refcell.borrow_mut();
+
+    enable_gpio(data, &mut mut_data, &[(pin, pin_state)])?;
+
+    Ok(FlexPin {
+        pin,
+        data: data.clone(),
+        mut_data_refcell: mut_data_refcell.clone(),
+    })
+}
+
+pub fn new_debounced_input(
+    data: &Shared<IOWarriorData>,
+    mut_data_refcell: &Shared<SharedCell<IOWarriorMutData>>,
+    pin: u8,
+    debounce: Debounce,
+) -> Result<DebouncedInput, PinSetupError> {
+    let input_pin = new_input(data, mut_data_refcell, pin)?;
+
+    Ok(into_debounced_input(input_pin, debounce))
+}
+
+pub fn into_debounced_input(input_pin: InputPin, debounce: Debounce) -> DebouncedInput {
+    let pin_state = get_pin_input_state(&input_pin.mut_data_refcell.borrow_mut(), input_pin.pin);
+
+    DebouncedInput {
+        input_pin,
+        debounce,
+        pin_state,
+        candidate: None,
+        events: VecDeque::new(),
+    }
+}
+
+pub fn new_gpio_port(
+    data: &Shared<IOWarriorData>,
+    mut_data_refcell: &Shared<SharedCell<IOWarriorMutData>>,
+    pins: &[u8],
+) -> Result<GpioPort, PinSetupError> {
+    if pins.len() > 32 {
+        return Err(PinSetupError::TooManyPins);
+    }
+
+    let mut mut_data = mut_data_refcell.borrow_mut();
+
+    let pin_states: Vec<(u8, PinState)> = pins.iter().map(|pin| (*pin, PinState::High)).collect();
+
+    enable_gpio(data, &mut mut_data, &pin_states)?;
+
+    Ok(GpioPort {
+        pins: pins.to_vec(),
+        data: data.clone(),
+        mut_data_refcell: mut_data_refcell.clone(),
+    })
+}
+
+#[cfg(feature = "sync")]
+pub fn new_waveform_player(
+    data: &Shared<IOWarriorData>,
+    mut_data_refcell: &Shared<SharedCell<IOWarriorMutData>>,
+    pins: &[u8],
+) -> Result<WaveformPlayer, PinSetupError> {
+    if pins.len() > 32 {
+        return Err(PinSetupError::TooManyPins);
+    }
+
+    let mut mut_data = mut_data_refcell.borrow_mut();
+
+    let pin_states: Vec<(u8, PinState)> = pins.iter().map(|pin| (*pin, PinState::High)).collect();
+
+    enable_gpio(data, &mut mut_data, &pin_states)?;
+
+    Ok(WaveformPlayer {
+        pins: pins.to_vec(),
+        data: data.clone(),
+        mut_data_refcell: mut_data_refcell.clone(),
+        cancelled: Default::default(),
+        thread: None,
+    })
+}
+
+#[cfg(feature = "sync")]
+pub fn new_pin_monitor(
+    data: &Shared<IOWarriorData>,
+    mut_data_refcell: &Shared<SharedCell<IOWarriorMutData>>,
+    pins: &[u8],
+) -> Result<PinMonitor, PinSetupError> {
     if data.device_type == IOWarriorType::IOWarrior28Dongle
         || data.device_type == IOWarriorType::IOWarrior56Dongle
     {
         return Err(PinSetupError::NotSupported);
     }
 
-    if !get_is_valid_gpio(data.device_type, pin) {
+    if !pins
+        .iter()
+        .all(|pin| get_is_valid_gpio(data.device_type, *pin))
+    {
         return Err(PinSetupError::PinNotExisting);
     }
 
-    match mut_data.pins_in_use.iter().filter(|x| x.pin == pin).next() {
-        None => {}
-        Some(used_pin) => {
-            return Err(match used_pin.peripheral {
-                None => PinSetupError::AlreadySetup,
-                Some(peripheral) => PinSetupError::BlockedByPeripheral(peripheral),
+    let (sender, receiver) = mpsc::channel();
+    let cancelled = Arc::new(AtomicBool::new(false));
+
+    let thread = {
+        let data = data.clone();
+        let mut_data_refcell = mut_data_refcell.clone();
+        let pins = pins.to_vec();
+        let cancelled = cancelled.clone();
+
+        std::thread::spawn(move || {
+            monitor_pins(&data, &mut_data_refcell, &pins, &sender, || {
+                cancelled.load(Ordering::Relaxed)
             })
+        })
+    };
+
+    Ok(PinMonitor {
+        pins: pins.to_vec(),
+        receiver,
+        cancelled,
+        thread: Some(thread),
+    })
+}
+
+fn enable_gpio(
+    data: &IOWarriorData,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
+    pin_states: &[(u8, PinState)],
+) -> Result<(), PinSetupError> {
+    if data.device_type == IOWarriorType::IOWarrior28Dongle
+        || data.device_type == IOWarriorType::IOWarrior56Dongle
+    {
+        return Err(PinSetupError::NotSupported);
+    }
+
+    for (index, (pin, _)) in pin_states.iter().enumerate() {
+        if !get_is_valid_gpio(data.device_type, *pin) {
+            return Err(PinSetupError::PinNotExisting);
+        }
+
+        if pin_states[..index].iter().any(|(x, _)| x == pin) {
+            return Err(PinSetupError::AlreadySetup);
+        }
+
+        match mut_data.pins_in_use.iter().find(|x| x.pin == *pin) {
+            None => {}
+            Some(used_pin) => {
+                return Err(match used_pin.peripheral {
+                    None => PinSetupError::AlreadySetup,
+                    Some(peripheral) => PinSetupError::BlockedByPeripheral(peripheral),
+                })
+            }
         }
     }
 
     peripheral_service::cleanup_dangling_modules(&data, mut_data)
         .map_err(|x| PinSetupError::ErrorUSB(x))?;
 
-    peripheral_service::set_pin_output(&data, mut_data, pin_state, pin)
-        .map_err(|x| PinSetupError::ErrorUSB(x))?;
+    peripheral_service::set_pins_output(data, mut_data, pin_states)
+        .map_err(PinSetupError::ErrorUSB)?;
 
-    mut_data.pins_in_use.push(UsedPin {
-        pin,
-        peripheral: None,
-    });
+    mut_data
+        .pins_in_use
+        .extend(pin_states.iter().map(|(pin, _)| UsedPin {
+            pin: *pin,
+            peripheral: None,
+        }));
 
     Ok(())
 }
 
 pub fn is_pin_input_state(
-    data: &Rc<IOWarriorData>,
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    data: &Shared<IOWarriorData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
     pin: u8,
     expected_pin_state: PinState,
 ) -> Result<bool, PinError> {
-    let report = communication_service::read_report_non_blocking(
+    update_pins_read_report(data, mut_data)?;
+
+    Ok(get_pin_input_state(mut_data, pin) == expected_pin_state)
+}
+
+pub fn get_pins_input_value(
+    data: &Shared<IOWarriorData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
+    pins: &[u8],
+) -> Result<u32, PinError> {
+    update_pins_read_report(data, mut_data)?;
+
+    Ok(pins
+        .iter()
+        .enumerate()
+        .filter(|(_, pin)| get_pin_input_state(mut_data, **pin) == PinState::High)
+        .fold(0u32, |value, (index, _)| value | (1u32 << index)))
+}
+
+fn update_pins_read_report(
+    data: &Shared<IOWarriorData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
+) -> Result<(), PinError> {
+    // Every pin change queues a report, only the latest one reflects the current pin states.
+    while let Some(report) = communication_service::read_report_non_blocking(
+        &mut mut_data.communication_data,
+        data.create_report(Pipe::IOPins),
+    )
+    .map_err(PinError::ErrorUSB)?
+    {
+        check_pins_read_report(&report)?;
+
+        mut_data.pins_read_report = report;
+    }
+
+    Ok(())
+}
+
+fn check_pins_read_report(report: &Report) -> Result<(), PinError> {
+    match report.buffer[0] == ReportId::GpioReadWrite.get_value() {
+        true => Ok(()),
+        false => Err(PinError::ProtocolError {
+            expected: ReportId::GpioReadWrite,
+            got: report.buffer[0],
+        }),
+    }
+}
+
+pub fn wait_for_pin_input_state(
+    data: &Shared<IOWarriorData>,
+    mut_data_refcell: &Shared<SharedCell<IOWarriorMutData>>,
+    pin: u8,
+    timeout: Option<Duration>,
+    condition: fn(PinState, PinState) -> bool,
+    is_cancelled: impl Fn() -> bool,
+) -> Result<bool, PinError> {
+    let deadline = timeout.map(|x| Instant::now() + x);
+
+    // Changes queued before the call are applied first, so they are not taken for an edge.
+    let mut pin_state = {
+        let mut mut_data = mut_data_refcell.borrow_mut();
+
+        update_pins_read_report(data, &mut mut_data)?;
+
+        get_pin_input_state(&mut_data, pin)
+    };
+
+    while !is_cancelled() {
+        let mut mut_data = mut_data_refcell.borrow_mut();
+
+        let report = match communication_service::read_report_non_blocking(
+            &mut mut_data.communication_data,
+            data.create_report(Pipe::IOPins),
+        )
+        .map_err(PinError::ErrorUSB)?
+        {
+            Some(report) => Some(report),
+            None => {
+                if condition(pin_state, pin_state) {
+                    return Ok(true);
+                }
+
+                let wait_duration = match deadline {
+                    None => WAIT_SLICE,
+                    Some(deadline) => {
+                        let remaining = deadline.saturating_duration_since(Instant::now());
+
+                        if remaining.is_zero() {
+                            return Ok(false);
+                        }
+
+                        Duration::min(remaining, WAIT_SLICE)
+                    }
+                };
+
+                // The board is locked while waiting, other calls on the board wait for at most one
+                // slice until the lock is handed over to them.
+                communication_service::read_report_timeout(
+                    &mut mut_data.communication_data,
+                    data.create_report(Pipe::IOPins),
+                    wait_duration,
+                )
+                .map_err(PinError::ErrorUSB)?
+            }
+        };
+
+        if let Some(report) = report {
+            check_pins_read_report(&report)?;
+
+            mut_data.pins_read_report = report;
+
+            let previous_pin_state = pin_state;
+
+            pin_state = get_pin_input_state(&mut_data, pin);
+
+            if condition(previous_pin_state, pin_state) {
+                return Ok(true);
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+pub fn read_pin_input_states(
+    data: &Shared<IOWarriorData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
+    pin: u8,
+    timeout: Duration,
+) -> Result<(Vec<PinState>, PinState), PinError> {
+    let mut pin_states = Vec::new();
+
+    let mut report = communication_service::read_report_timeout(
         &mut mut_data.communication_data,
         data.create_report(Pipe::IOPins),
+        timeout,
     )
-    .map_err(|x| PinError::ErrorUSB(x))?;
+    .map_err(PinError::ErrorUSB)?;
+
+    while let Some(x) = report {
+        check_pins_read_report(&x)?;
+
+        mut_data.pins_read_report = x;
+
+        pin_states.push(get_pin_input_state(mut_data, pin));
+
+        report = communication_service::read_report_non_blocking(
+            &mut mut_data.communication_data,
+            data.create_report(Pipe::IOPins),
+        )
+        .map_err(PinError::ErrorUSB)?;
+    }
+
+    // The latched state also holds changes whose reports were read by other handles of the board.
+    Ok((pin_states, get_pin_input_state(mut_data, pin)))
+}
+
+pub fn monitor_pins(
+    data: &Shared<IOWarriorData>,
+    mut_data_refcell: &Shared<SharedCell<IOWarriorMutData>>,
+    pins: &[u8],
+    sender: &Sender<PinEvent>,
+    is_cancelled: impl Fn() -> bool,
+) -> Result<(), PinError> {
+    while !is_cancelled() {
+        let mut mut_data = mut_data_refcell.borrow_mut();
+
+        // The lock is handed over after every slice, other handles wait for at most one slice.
+        let report = communication_service::read_report_timeout(
+            &mut mut_data.communication_data,
+            data.create_report(Pipe::IOPins),
+            WAIT_SLICE,
+        )
+        .map_err(PinError::ErrorUSB)?;
+
+        let instant = Instant::now();
+
+        if let Some(report) = report {
+            let previous_pin_states: Vec<PinState> = pins
+                .iter()
+                .map(|pin| get_pin_input_state(&mut_data, *pin))
+                .collect();
+
+            check_pins_read_report(&report)?;
 
-    match report {
-        None => {}
-        Some(report) => {
             mut_data.pins_read_report = report;
+
+            for (pin, previous_pin_state) in pins.iter().zip(previous_pin_states) {
+                let pin_state = get_pin_input_state(&mut_data, *pin);
+
+                if pin_state != previous_pin_state {
+                    let pin_event = PinEvent {
+                        instant,
+                        pin: *pin,
+                        pin_state,
+                    };
+
+                    if sender.send(pin_event).is_err() {
+                        return Ok(());
+                    }
+                }
+            }
         }
-    };
+    }
 
-    let byte_index = ((pin as usize) / 8usize) + 1;
-    let bit_index = Bit::from_u8(pin % 8u8);
+    Ok(())
+}
 
-    let value = mut_data.pins_read_report.buffer[byte_index].get_bit(bit_index);
+fn get_pin_input_state(mut_data: &IOWarriorMutData, pin: u8) -> PinState {
+    let byte_index = ((pin as usize) / 8usize) + 1;
+    let bit_index = Bit::from_pin(pin);
 
-    Ok(match expected_pin_state {
-        PinState::Low => !value,
-        PinState::High => value,
-    })
+    PinState::from(mut_data.pins_read_report.buffer[byte_index].get_bit(bit_index))
 }
 
 pub fn set_pin_output_state(
     data: &IOWarriorData,
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
     pin: u8,
     pin_state: PinState,
 ) -> Result<(), PinError> {
@@ -122,14 +467,115 @@ pub fn set_pin_output_state(
         .map_err(|x| PinError::ErrorUSB(x))
 }
 
+pub fn set_pins_output_value(
+    data: &IOWarriorData,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
+    pins: &[u8],
+    mask: u32,
+    value: u32,
+) -> Result<(), PinError> {
+    let pin_states: Vec<(u8, PinState)> = pins
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| mask & (1u32 << index) != 0)
+        .map(|(index, pin)| (*pin, PinState::from(value & (1u32 << index) != 0)))
+        .collect();
+
+    if pin_states.is_empty() {
+        return Ok(());
+    }
+
+    peripheral_service::set_pins_output(data, mut_data, &pin_states).map_err(PinError::ErrorUSB)
+}
+
+pub fn play_waveform(
+    data: &Shared<IOWarriorData>,
+    mut_data_refcell: &Shared<SharedCell<IOWarriorMutData>>,
+    pins: &[u8],
+    steps: &[WaveformStep],
+    is_cancelled: impl Fn() -> bool,
+) -> Result<WaveformStatistics, PinError> {
+    let start = Instant::now();
+
+    let mut deadline = start;
+    let mut statistics = WaveformStatistics::default();
+    let mut total_jitter = Duration::ZERO;
+
+    for step in steps {
+        if !sleep_until(deadline, &is_cancelled) {
+            break;
+        }
+
+        let jitter = Instant::now().saturating_duration_since(deadline);
+
+        set_pins_output_value(
+            data,
+            &mut mut_data_refcell.borrow_mut(),
+            pins,
+            step.mask,
+            step.value,
+        )?;
+
+        statistics.steps += 1;
+        statistics.max_jitter = Duration::max(statistics.max_jitter, jitter);
+        total_jitter += jitter;
+
+        deadline += step.duration;
+    }
+
+    if statistics.steps == steps.len() {
+        sleep_until(deadline, &is_cancelled);
+    }
+
+    statistics.duration = start.elapsed();
+
+    if statistics.steps > 0 {
+        statistics.mean_jitter = total_jitter / statistics.steps as u32;
+    }
+
+    Ok(statistics)
+}
+
+fn sleep_until(deadline: Instant, is_cancelled: &impl Fn() -> bool) -> bool {
+    loop {
+        if is_cancelled() {
+            return false;
+        }
+
+        let remaining = deadline.saturating_duration_since(Instant::now());
+
+        if remaining.is_zero() {
+            return true;
+        }
+
+        // Sleeping is too coarse for the last moments, so the thread spins until the deadline.
+        match remaining > SPIN_THRESHOLD {
+            true => std::thread::sleep(Duration::min(remaining - SPIN_THRESHOLD, WAIT_SLICE)),
+            false => std::hint::spin_loop(),
+        }
+    }
+}
+
+pub fn get_pins_output_value(mut_data: &IOWarriorMutData, pins: &[u8]) -> u32 {
+    pins.iter()
+        .enumerate()
+        .filter(|(_, pin)| {
+            let byte_index = ((**pin as usize) / 8usize) + 1;
+            let bit_index = Bit::from_pin(**pin);
+
+            mut_data.pins_write_report.buffer[byte_index].get_bit(bit_index)
+        })
+        .fold(0u32, |value, (index, _)| value | (1u32 << index))
+}
+
 pub fn is_pin_output_state(
     data: &IOWarriorData,
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
     pin: u8,
     expected_pin_state: PinState,
 ) -> Result<bool, PinError> {
     let byte_index = ((pin as usize) / 8usize) + 1;
-    let bit_index = Bit::from_u8(pin % 8u8);
+    let bit_index = Bit::from_pin(pin);
 
     let value = mut_data.pins_write_report.buffer[byte_index].get_bit(bit_index);
 
@@ -0,0 +1,171 @@
+use crate::digital::{digital_service, PinError};
+use crate::iowarrior::{peripheral_service, IOWarriorData, IOWarriorMutData, Shared, SharedCell};
+use embedded_hal::digital::PinState;
+use std::fmt;
+
+#[derive(Debug)]
+pub struct FlexPin {
+    pub(crate) data: Shared<IOWarriorData>,
+    pub(crate) mut_data_refcell: Shared<SharedCell<IOWarriorMutData>>,
+    pub(crate) pin: u8,
+}
+
+impl embedded_hal::digital::ErrorType for FlexPin {
+    type Error = PinError;
+}
+
+impl embedded_hal::digital::InputPin for FlexPin {
+    #[inline]
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        digital_service::is_pin_input_state(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            self.pin,
+            PinState::High,
+        )
+    }
+
+    #[inline]
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        digital_service::is_pin_input_state(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            self.pin,
+            PinState::Low,
+        )
+    }
+}
+
+impl embedded_hal::digital::OutputPin for FlexPin {
+    #[inline]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        digital_service::set_pin_output_state(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            self.pin,
+            PinState::Low,
+        )
+    }
+
+    #[inline]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        digital_service::set_pin_output_state(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            self.pin,
+            PinState::High,
+        )
+    }
+}
+
+impl embedded_hal::digital::StatefulOutputPin for FlexPin {
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        digital_service::is_pin_output_state(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            self.pin,
+            PinState::High,
+        )
+    }
+
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        digital_service::is_pin_output_state(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            self.pin,
+            PinState::Low,
+        )
+    }
+}
+
+#[cfg(feature = "embedded-hal-0")]
+impl embedded_hal_0::digital::v2::InputPin for FlexPin {
+    type Error = PinError;
+
+    #[inline]
+    fn is_high(&self) -> Result<bool, Self::Error> {
+        digital_service::is_pin_input_state(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            self.pin,
+            PinState::High,
+        )
+    }
+
+    #[inline]
+    fn is_low(&self) -> Result<bool, Self::Error> {
+        digital_service::is_pin_input_state(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            self.pin,
+            PinState::Low,
+        )
+    }
+}
+
+#[cfg(feature = "embedded-hal-0")]
+impl embedded_hal_0::digital::v2::OutputPin for FlexPin {
+    type Error = PinError;
+
+    #[inline]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        digital_service::set_pin_output_state(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            self.pin,
+            PinState::Low,
+        )
+    }
+
+    #[inline]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        digital_service::set_pin_output_state(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            self.pin,
+            PinState::High,
+        )
+    }
+}
+
+#[cfg(feature = "embedded-hal-0")]
+impl embedded_hal_0::digital::v2::StatefulOutputPin for FlexPin {
+    #[inline]
+    fn is_set_high(&self) -> Result<bool, Self::Error> {
+        digital_service::is_pin_output_state(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            self.pin,
+            PinState::High,
+        )
+    }
+
+    #[inline]
+    fn is_set_low(&self) -> Result<bool, Self::Error> {
+        digital_service::is_pin_output_state(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            self.pin,
+            PinState::Low,
+        )
+    }
+}
+
+impl fmt::Display for FlexPin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Drop for FlexPin {
+    #[inline]
+    fn drop(&mut self) {
+        peripheral_service::disable_gpio(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            &[self.pin],
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use crate::digital::{digital_service, PinError};
+use crate::iowarrior::{peripheral_service, IOWarriorData, IOWarriorMutData, Shared, SharedCell};
+use std::fmt;
+
+#[derive(Debug)]
+pub struct GpioPort {
+    pub(crate) data: Shared<IOWarriorData>,
+    pub(crate) mut_data_refcell: Shared<SharedCell<IOWarriorMutData>>,
+    pub(crate) pins: Vec<u8>,
+}
+
+impl GpioPort {
+    #[inline]
+    pub fn get_pins(&self) -> &[u8] {
+        &self.pins
+    }
+
+    #[inline]
+    pub fn write(&mut self, value: impl Into<u32>) -> Result<(), PinError> {
+        digital_service::set_pins_output_value(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            &self.pins,
+            u32::MAX,
+            value.into(),
+        )
+    }
+
+    #[inline]
+    pub fn write_masked(
+        &mut self,
+        mask: impl Into<u32>,
+        value: impl Into<u32>,
+    ) -> Result<(), PinError> {
+        digital_service::set_pins_output_value(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            &self.pins,
+            mask.into(),
+            value.into(),
+        )
+    }
+
+    #[inline]
+    pub fn read(&mut self) -> Result<u32, PinError> {
+        digital_service::get_pins_input_value(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            &self.pins,
+        )
+    }
+
+    #[inline]
+    pub fn get_output(&self) -> u32 {
+        digital_service::get_pins_output_value(&self.mut_data_refcell.borrow_mut(), &self.pins)
+    }
+}
+
+impl fmt::Display for GpioPort {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Drop for GpioPort {
+    #[inline]
+    fn drop(&mut self) {
+        peripheral_service::disable_gpio(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            &self.pins,
+        );
+    }
+}
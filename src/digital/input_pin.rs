@@ -1,16 +1,16 @@
 use crate::digital::digital_service;
-use crate::digital::PinError;
-use crate::iowarrior::{peripheral_service, IOWarriorData, IOWarriorMutData};
+use crate::digital::{OutputPin, PinError};
+use crate::iowarrior::{peripheral_service, IOWarriorData, IOWarriorMutData, Shared, SharedCell};
 use embedded_hal::digital::PinState;
-use std::cell::RefCell;
 use std::fmt;
-use std::rc::Rc;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub struct InputPin {
-    pub(crate) data: Rc<IOWarriorData>,
-    pub(crate) mut_data_refcell: Rc<RefCell<IOWarriorMutData>>,
+    pub(crate) data: Shared<IOWarriorData>,
+    pub(crate) mut_data_refcell: Shared<SharedCell<IOWarriorMutData>>,
     pub(crate) pin: u8,
+    pub(crate) is_converted: bool,
 }
 
 impl embedded_hal::digital::ErrorType for InputPin {
@@ -73,10 +73,87 @@ impl fmt::Display for InputPin {
 impl Drop for InputPin {
     #[inline]
     fn drop(&mut self) {
+        if self.is_converted {
+            return;
+        }
+
         peripheral_service::disable_gpio(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            &[self.pin],
+        );
+    }
+}
+
+impl InputPin {
+    #[inline]
+    pub fn into_output(mut self, pin_state: PinState) -> Result<OutputPin, (InputPin, PinError)> {
+        let result = digital_service::set_pin_output_state(
             &self.data,
             &mut self.mut_data_refcell.borrow_mut(),
             self.pin,
+            pin_state,
         );
+
+        if let Err(error) = result {
+            return Err((self, error));
+        }
+
+        // The pin stays in use, so dropping this handle must not release it.
+        self.is_converted = true;
+
+        Ok(OutputPin {
+            data: self.data.clone(),
+            mut_data_refcell: self.mut_data_refcell.clone(),
+            pin: self.pin,
+            is_converted: false,
+        })
+    }
+
+    #[inline]
+    pub fn wait_for_high(&mut self, timeout: Option<Duration>) -> Result<(), PinError> {
+        self.wait_for(timeout, |_, current| current == PinState::High)
+    }
+
+    #[inline]
+    pub fn wait_for_low(&mut self, timeout: Option<Duration>) -> Result<(), PinError> {
+        self.wait_for(timeout, |_, current| current == PinState::Low)
+    }
+
+    #[inline]
+    pub fn wait_for_rising_edge(&mut self, timeout: Option<Duration>) -> Result<(), PinError> {
+        self.wait_for(timeout, |previous, current| {
+            previous == PinState::Low && current == PinState::High
+        })
+    }
+
+    #[inline]
+    pub fn wait_for_falling_edge(&mut self, timeout: Option<Duration>) -> Result<(), PinError> {
+        self.wait_for(timeout, |previous, current| {
+            previous == PinState::High && current == PinState::Low
+        })
+    }
+
+    #[inline]
+    pub fn wait_for_any_edge(&mut self, timeout: Option<Duration>) -> Result<(), PinError> {
+        self.wait_for(timeout, |previous, current| previous != current)
+    }
+
+    fn wait_for(
+        &mut self,
+        timeout: Option<Duration>,
+        condition: fn(PinState, PinState) -> bool,
+    ) -> Result<(), PinError> {
+        match digital_service::wait_for_pin_input_state(
+            &self.data,
+            &self.mut_data_refcell,
+            self.pin,
+            timeout,
+            condition,
+            || false,
+        )? {
+            true => Ok(()),
+            false => Err(PinError::Timeout),
+        }
     }
 }
@@ -0,0 +1,64 @@
+use crate::digital::{digital_service, InputPin, PinError};
+use crate::iowarrior::unblock;
+use embedded_hal::digital::PinState;
+
+impl embedded_hal_async::digital::Wait for InputPin {
+    #[inline]
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_condition(|_, current| current == PinState::High)
+            .await
+    }
+
+    #[inline]
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_condition(|_, current| current == PinState::Low)
+            .await
+    }
+
+    #[inline]
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_condition(|previous, current| {
+            previous == PinState::Low && current == PinState::High
+        })
+        .await
+    }
+
+    #[inline]
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_condition(|previous, current| {
+            previous == PinState::High && current == PinState::Low
+        })
+        .await
+    }
+
+    #[inline]
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_condition(|previous, current| previous != current)
+            .await
+    }
+}
+
+impl InputPin {
+    async fn wait_for_condition(
+        &mut self,
+        condition: fn(PinState, PinState) -> bool,
+    ) -> Result<(), PinError> {
+        let data = self.data.clone();
+        let mut_data_refcell = self.mut_data_refcell.clone();
+        let pin = self.pin;
+
+        unblock(move |cancellation_token| {
+            digital_service::wait_for_pin_input_state(
+                &data,
+                &mut_data_refcell,
+                pin,
+                None,
+                condition,
+                || cancellation_token.is_cancelled(),
+            )
+        })
+        .await?;
+
+        Ok(())
+    }
+}
@@ -1,10 +1,34 @@
+mod debounce;
+mod debounced_input;
 pub(crate) mod digital_service;
+mod flex_pin;
+mod gpio_port;
 mod input_pin;
+#[cfg(feature = "embedded-hal-async")]
+mod input_pin_async;
 mod output_pin;
 mod pin_error;
+mod pin_event;
+#[cfg(feature = "sync")]
+mod pin_monitor;
 mod pin_setup_error;
+#[cfg(feature = "sync")]
+mod waveform_player;
+mod waveform_statistics;
+mod waveform_step;
 
+pub use self::debounce::*;
+pub use self::debounced_input::*;
+pub use self::flex_pin::*;
+pub use self::gpio_port::*;
 pub use self::input_pin::*;
 pub use self::output_pin::*;
 pub use self::pin_error::*;
+pub use self::pin_event::*;
+#[cfg(feature = "sync")]
+pub use self::pin_monitor::*;
 pub use self::pin_setup_error::*;
+#[cfg(feature = "sync")]
+pub use self::waveform_player::*;
+pub use self::waveform_statistics::*;
+pub use self::waveform_step::*;
@@ -1,15 +1,14 @@
-use crate::digital::{digital_service, PinError};
-use crate::iowarrior::{peripheral_service, IOWarriorData, IOWarriorMutData};
+use crate::digital::{digital_service, InputPin, PinError};
+use crate::iowarrior::{peripheral_service, IOWarriorData, IOWarriorMutData, Shared, SharedCell};
 use embedded_hal::digital::PinState;
-use std::cell::RefCell;
 use std::fmt;
-use std::rc::Rc;
 
 #[derive(Debug)]
 pub struct OutputPin {
-    pub(crate) data: Rc<IOWarriorData>,
-    pub(crate) mut_data_refcell: Rc<RefCell<IOWarriorMutData>>,
+    pub(crate) data: Shared<IOWarriorData>,
+    pub(crate) mut_data_refcell: Shared<SharedCell<IOWarriorMutData>>,
     pub(crate) pin: u8,
+    pub(crate) is_converted: bool,
 }
 
 impl embedded_hal::digital::ErrorType for OutputPin {
@@ -108,6 +107,32 @@ impl embedded_hal_0::digital::v2::StatefulOutputPin for OutputPin {
     }
 }
 
+impl OutputPin {
+    #[inline]
+    pub fn into_input(mut self) -> Result<InputPin, (OutputPin, PinError)> {
+        let result = digital_service::set_pin_output_state(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            self.pin,
+            PinState::High,
+        );
+
+        if let Err(error) = result {
+            return Err((self, error));
+        }
+
+        // The pin stays in use, so dropping this handle must not release it.
+        self.is_converted = true;
+
+        Ok(InputPin {
+            data: self.data.clone(),
+            mut_data_refcell: self.mut_data_refcell.clone(),
+            pin: self.pin,
+            is_converted: false,
+        })
+    }
+}
+
 impl fmt::Display for OutputPin {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:?}", self)
@@ -117,10 +142,14 @@ impl fmt::Display for OutputPin {
 impl Drop for OutputPin {
     #[inline]
     fn drop(&mut self) {
+        if self.is_converted {
+            return;
+        }
+
         peripheral_service::disable_gpio(
             &self.data,
             &mut self.mut_data_refcell.borrow_mut(),
-            self.pin,
+            &[self.pin],
         );
     }
 }
@@ -1,3 +1,4 @@
+use crate::iowarrior::ReportId;
 use embedded_hal::digital::ErrorKind;
 use hidapi::HidError;
 use thiserror::Error;
@@ -7,12 +8,21 @@ use thiserror::Error;
 pub enum PinError {
     #[error("USB HID error.")]
     ErrorUSB(HidError),
+    #[error("Timeout while waiting for pin state.")]
+    Timeout,
+    #[error("Pin expected report {expected}, got {got:#04x}.")]
+    ProtocolError { expected: ReportId, got: u8 },
+    #[error("Pin monitor is stopped.")]
+    MonitorStopped,
 }
 
 impl embedded_hal::digital::Error for PinError {
     fn kind(&self) -> ErrorKind {
         match self {
             PinError::ErrorUSB(_) => ErrorKind::Other,
+            PinError::Timeout => ErrorKind::Other,
+            PinError::ProtocolError { .. } => ErrorKind::Other,
+            PinError::MonitorStopped => ErrorKind::Other,
         }
     }
 }
@@ -0,0 +1,16 @@
+use embedded_hal::digital::PinState;
+use std::fmt;
+use std::time::Instant;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PinEvent {
+    pub instant: Instant,
+    pub pin: u8,
+    pub pin_state: PinState,
+}
+
+impl fmt::Display for PinEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
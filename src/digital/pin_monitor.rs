@@ -0,0 +1,77 @@
+use crate::digital::{PinError, PinEvent};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+#[derive(Debug)]
+pub struct PinMonitor {
+    pub(crate) pins: Vec<u8>,
+    pub(crate) receiver: Receiver<PinEvent>,
+    pub(crate) cancelled: Arc<AtomicBool>,
+    pub(crate) thread: Option<JoinHandle<Result<(), PinError>>>,
+}
+
+impl PinMonitor {
+    #[inline]
+    pub fn get_pins(&self) -> &[u8] {
+        &self.pins
+    }
+
+    #[inline]
+    pub fn next_event(&self) -> Option<PinEvent> {
+        self.receiver.recv().ok()
+    }
+
+    #[inline]
+    pub fn next_event_timeout(&self, timeout: Duration) -> Result<Option<PinEvent>, PinError> {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(pin_event) => Ok(Some(pin_event)),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => Err(PinError::MonitorStopped),
+        }
+    }
+
+    #[inline]
+    pub fn try_next_event(&self) -> Option<PinEvent> {
+        self.receiver.try_recv().ok()
+    }
+
+    #[inline]
+    pub fn is_running(&self) -> bool {
+        match &self.thread {
+            None => false,
+            Some(thread) => !thread.is_finished(),
+        }
+    }
+
+    pub fn stop(&mut self) -> Result<(), PinError> {
+        self.cancelled.store(true, Ordering::Relaxed);
+
+        match self.thread.take() {
+            None => Ok(()),
+            Some(thread) => thread
+                .join()
+                .unwrap_or_else(|x| std::panic::resume_unwind(x)),
+        }
+    }
+}
+
+impl fmt::Display for PinMonitor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Drop for PinMonitor {
+    #[inline]
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -15,4 +15,6 @@ pub enum PinSetupError {
     BlockedByPeripheral(Peripheral),
     #[error("Pins are not supported by hardware.")]
     NotSupported,
+    #[error("Too many pins, a port holds up to 32 pins.")]
+    TooManyPins,
 }
@@ -0,0 +1,85 @@
+use crate::digital::{digital_service, PinError, WaveformStatistics, WaveformStep};
+use crate::iowarrior::{peripheral_service, IOWarriorData, IOWarriorMutData, Shared, SharedCell};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+#[derive(Debug)]
+pub struct WaveformPlayer {
+    pub(crate) data: Shared<IOWarriorData>,
+    pub(crate) mut_data_refcell: Shared<SharedCell<IOWarriorMutData>>,
+    pub(crate) pins: Vec<u8>,
+    pub(crate) cancelled: Arc<AtomicBool>,
+    pub(crate) thread: Option<JoinHandle<Result<WaveformStatistics, PinError>>>,
+}
+
+impl WaveformPlayer {
+    #[inline]
+    pub fn get_pins(&self) -> &[u8] {
+        &self.pins
+    }
+
+    pub fn play(&mut self, steps: Vec<WaveformStep>) {
+        let _ = self.stop();
+
+        let data = self.data.clone();
+        let mut_data_refcell = self.mut_data_refcell.clone();
+        let pins = self.pins.clone();
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        self.cancelled = cancelled.clone();
+        self.thread = Some(std::thread::spawn(move || {
+            digital_service::play_waveform(&data, &mut_data_refcell, &pins, &steps, || {
+                cancelled.load(Ordering::Relaxed)
+            })
+        }));
+    }
+
+    #[inline]
+    pub fn is_playing(&self) -> bool {
+        match &self.thread {
+            None => false,
+            Some(thread) => !thread.is_finished(),
+        }
+    }
+
+    pub fn wait(&mut self) -> Result<WaveformStatistics, PinError> {
+        match self.thread.take() {
+            None => Ok(WaveformStatistics::default()),
+            Some(thread) => thread
+                .join()
+                .unwrap_or_else(|x| std::panic::resume_unwind(x)),
+        }
+    }
+
+    #[inline]
+    pub fn stop(&mut self) -> Result<WaveformStatistics, PinError> {
+        self.cancelled.store(true, Ordering::Relaxed);
+
+        self.wait()
+    }
+}
+
+impl fmt::Display for WaveformPlayer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Drop for WaveformPlayer {
+    #[inline]
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+
+        peripheral_service::disable_gpio(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            &self.pins,
+        );
+    }
+}
@@ -0,0 +1,16 @@
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct WaveformStatistics {
+    pub steps: usize,
+    pub duration: Duration,
+    pub mean_jitter: Duration,
+    pub max_jitter: Duration,
+}
+
+impl fmt::Display for WaveformStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
@@ -0,0 +1,15 @@
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct WaveformStep {
+    pub mask: u32,
+    pub value: u32,
+    pub duration: Duration,
+}
+
+impl fmt::Display for WaveformStep {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
@@ -1,13 +1,14 @@
-use crate::i2c::{i2c_service, I2CConfig, I2CError};
-use crate::iowarrior::{peripheral_service, IOWarriorData, IOWarriorMutData, Peripheral};
-use std::cell::RefCell;
+use crate::i2c::{i2c_service, I2CAddress, I2CConfig, I2CError, SharedI2C};
+use crate::iowarrior::{
+    peripheral_service, IOWarriorData, IOWarriorMutData, Peripheral, Shared, SharedCell,
+};
+use embedded_hal::i2c::SevenBitAddress;
 use std::fmt;
-use std::rc::Rc;
 
 #[derive(Debug)]
 pub struct I2C {
-    pub(crate) data: Rc<IOWarriorData>,
-    pub(crate) mut_data_refcell: Rc<RefCell<IOWarriorMutData>>,
+    pub(crate) data: Shared<IOWarriorData>,
+    pub(crate) mut_data_refcell: Shared<SharedCell<IOWarriorMutData>>,
     pub(crate) i2c_config: I2CConfig,
 }
 
@@ -39,20 +40,30 @@ impl embedded_hal::i2c::I2c<embedded_hal::i2c::SevenBitAddress> for I2C {
         address: embedded_hal::i2c::SevenBitAddress,
         operations: &mut [embedded_hal::i2c::Operation],
     ) -> Result<(), Self::Error> {
-        let mut mut_data = self.mut_data_refcell.borrow_mut();
-
-        for operation in operations {
-            match operation {
-                embedded_hal::i2c::Operation::Read(buffer) => {
-                    i2c_service::read_data(&self.data, &mut mut_data, address, buffer)?;
-                }
-                embedded_hal::i2c::Operation::Write(buffer) => {
-                    i2c_service::write_data(&self.data, &mut mut_data, address, buffer)?;
-                }
-            }
-        }
+        i2c_service::transaction(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            &self.i2c_config,
+            I2CAddress::SevenBit(address),
+            operations,
+        )
+    }
+}
 
-        Ok(())
+impl embedded_hal::i2c::I2c<embedded_hal::i2c::TenBitAddress> for I2C {
+    #[inline]
+    fn transaction(
+        &mut self,
+        address: embedded_hal::i2c::TenBitAddress,
+        operations: &mut [embedded_hal::i2c::Operation],
+    ) -> Result<(), Self::Error> {
+        i2c_service::transaction(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            &self.i2c_config,
+            I2CAddress::TenBit(address),
+            operations,
+        )
     }
 }
 
@@ -65,6 +76,7 @@ impl embedded_hal_0::blocking::i2c::Write for I2C {
         i2c_service::write_data(
             &self.data,
             &mut self.mut_data_refcell.borrow_mut(),
+            &self.i2c_config,
             address,
             bytes,
         )
@@ -80,6 +92,7 @@ impl embedded_hal_0::blocking::i2c::Read for I2C {
         i2c_service::read_data(
             &self.data,
             &mut self.mut_data_refcell.borrow_mut(),
+            &self.i2c_config,
             address,
             buffer,
         )
@@ -97,10 +110,16 @@ impl embedded_hal_0::blocking::i2c::WriteRead for I2C {
         bytes: &[u8],
         buffer: &mut [u8],
     ) -> Result<(), Self::Error> {
-        let mut mut_data = self.mut_data_refcell.borrow_mut();
-
-        i2c_service::write_data(&self.data, &mut mut_data, address, bytes)?;
-        i2c_service::read_data(&self.data, &mut mut_data, address, buffer)
+        i2c_service::transaction(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            &self.i2c_config,
+            I2CAddress::SevenBit(address),
+            &mut [
+                embedded_hal::i2c::Operation::Write(bytes),
+                embedded_hal::i2c::Operation::Read(buffer),
+            ],
+        )
     }
 }
 
@@ -109,4 +128,42 @@ impl I2C {
     pub fn get_config(&self) -> I2CConfig {
         self.i2c_config
     }
+
+    #[inline]
+    pub fn set_config(&mut self, i2c_config: I2CConfig) -> Result<(), I2CError> {
+        i2c_service::set_config(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            i2c_config,
+        )?;
+
+        self.i2c_config = i2c_config;
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn scan(&mut self) -> Result<Vec<SevenBitAddress>, I2CError> {
+        i2c_service::scan(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            &self.i2c_config,
+        )
+    }
+
+    #[inline]
+    pub fn into_shared(self) -> SharedI2C {
+        SharedI2C {
+            i2c: Shared::new(SharedCell::new(self)),
+        }
+    }
+
+    #[inline]
+    pub fn recover_bus(&mut self) -> Result<(), I2CError> {
+        i2c_service::recover_bus(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            &self.i2c_config,
+        )
+    }
 }
@@ -0,0 +1,46 @@
+use crate::i2c::I2CError;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum I2CAddress {
+    SevenBit(u8),
+    TenBit(u16),
+}
+
+impl fmt::Display for I2CAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl I2CAddress {
+    pub fn check_valid(&self) -> Result<(), I2CError> {
+        match self {
+            I2CAddress::SevenBit(address) => match *address > 0 && *address < 0x78 {
+                true => Ok(()),
+                false => Err(I2CError::InvalidAddress),
+            },
+            I2CAddress::TenBit(address) => match *address <= 0x3FF {
+                true => Ok(()),
+                false => Err(I2CError::InvalidTenBitAddress),
+            },
+        }
+    }
+
+    pub fn get_write_header(&self) -> Vec<u8> {
+        match self {
+            I2CAddress::SevenBit(address) => vec![address << 1],
+            I2CAddress::TenBit(address) => vec![
+                0xF0 | ((address >> 7) as u8 & 0x06), // 11110xx0
+                *address as u8,
+            ],
+        }
+    }
+
+    pub fn get_read_header(&self) -> u8 {
+        match self {
+            I2CAddress::SevenBit(address) => (address << 1) | 0x01,
+            I2CAddress::TenBit(address) => 0xF1 | ((address >> 7) as u8 & 0x06), // 11110xx1
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use crate::i2c::{i2c_service, I2CAddress, I2CConfig, I2CError, SharedI2C, I2C};
+use crate::iowarrior::{unblock, IOWarriorData, IOWarriorMutData, Shared, SharedCell};
+use embedded_hal_async::i2c::{Operation, SevenBitAddress, TenBitAddress};
+use std::fmt;
+
+#[derive(Debug)]
+enum OwnedOperation {
+    Read(Vec<u8>),
+    Write(Vec<u8>),
+}
+
+impl fmt::Display for OwnedOperation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl embedded_hal_async::i2c::I2c<SevenBitAddress> for I2C {
+    #[inline]
+    async fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        transaction_async(
+            self.data.clone(),
+            self.mut_data_refcell.clone(),
+            self.i2c_config,
+            I2CAddress::SevenBit(address),
+            operations,
+        )
+        .await
+    }
+}
+
+impl embedded_hal_async::i2c::I2c<TenBitAddress> for I2C {
+    #[inline]
+    async fn transaction(
+        &mut self,
+        address: TenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        transaction_async(
+            self.data.clone(),
+            self.mut_data_refcell.clone(),
+            self.i2c_config,
+            I2CAddress::TenBit(address),
+            operations,
+        )
+        .await
+    }
+}
+
+impl embedded_hal_async::i2c::I2c<SevenBitAddress> for SharedI2C {
+    #[inline]
+    async fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        shared_transaction_async(self, I2CAddress::SevenBit(address), operations).await
+    }
+}
+
+impl embedded_hal_async::i2c::I2c<TenBitAddress> for SharedI2C {
+    #[inline]
+    async fn transaction(
+        &mut self,
+        address: TenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        shared_transaction_async(self, I2CAddress::TenBit(address), operations).await
+    }
+}
+
+async fn shared_transaction_async(
+    shared_i2c: &SharedI2C,
+    address: I2CAddress,
+    operations: &mut [Operation<'_>],
+) -> Result<(), I2CError> {
+    // The lock of the SharedI2C is not held across the await.
+    let (data, mut_data_refcell, i2c_config) = {
+        let i2c = shared_i2c.i2c.borrow_mut();
+
+        (
+            i2c.data.clone(),
+            i2c.mut_data_refcell.clone(),
+            i2c.i2c_config,
+        )
+    };
+
+    transaction_async(data, mut_data_refcell, i2c_config, address, operations).await
+}
+
+async fn transaction_async(
+    data: Shared<IOWarriorData>,
+    mut_data_refcell: Shared<SharedCell<IOWarriorMutData>>,
+    i2c_config: I2CConfig,
+    address: I2CAddress,
+    operations: &mut [Operation<'_>],
+) -> Result<(), I2CError> {
+    let mut owned_operations: Vec<OwnedOperation> = operations
+        .iter()
+        .map(|operation| match operation {
+            Operation::Read(buffer) => OwnedOperation::Read(vec![0u8; buffer.len()]),
+            Operation::Write(buffer) => OwnedOperation::Write(buffer.to_vec()),
+        })
+        .collect();
+
+    let owned_operations = unblock(move |_| {
+        {
+            let mut borrowed_operations: Vec<Operation> = owned_operations
+                .iter_mut()
+                .map(|operation| match operation {
+                    OwnedOperation::Read(buffer) => Operation::Read(buffer),
+                    OwnedOperation::Write(buffer) => Operation::Write(buffer),
+                })
+                .collect();
+
+            i2c_service::transaction(
+                &data,
+                &mut mut_data_refcell.borrow_mut(),
+                &i2c_config,
+                address,
+                &mut borrowed_operations,
+            )?;
+        }
+
+        Ok::<Vec<OwnedOperation>, I2CError>(owned_operations)
+    })
+    .await?;
+
+    for (operation, owned_operation) in operations.iter_mut().zip(owned_operations) {
+        if let (Operation::Read(buffer), OwnedOperation::Read(read)) = (operation, owned_operation)
+        {
+            buffer.copy_from_slice(&read);
+        }
+    }
+
+    Ok(())
+}
@@ -1,9 +1,12 @@
 use std::fmt;
+use std::time::Duration;
 
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[non_exhaustive]
 pub struct I2CConfig {
     pub iow56_clock: IOW56Clock,
     pub iow100_speed: IOW100Speed,
+    pub timeout: Option<Duration>,
 }
 
 impl Default for I2CConfig {
@@ -11,6 +14,7 @@ impl Default for I2CConfig {
         I2CConfig {
             iow56_clock: IOW56Clock::Standard93kHz,
             iow100_speed: IOW100Speed::Standard100kb,
+            timeout: Some(Duration::from_secs(1)),
         }
     }
 }
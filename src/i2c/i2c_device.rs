@@ -0,0 +1,125 @@
+use crate::i2c::{I2CAddress, I2CError, I2CRegisterWidth, I2C};
+use embedded_hal::i2c::{I2c, SevenBitAddress};
+use std::fmt;
+
+#[derive(Debug)]
+pub struct I2CDevice<T = I2C> {
+    pub(crate) i2c: T,
+    pub(crate) address: SevenBitAddress,
+    pub(crate) register_width: I2CRegisterWidth,
+}
+
+impl<T: fmt::Debug> fmt::Display for I2CDevice<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl<T: I2c<SevenBitAddress, Error = I2CError>> I2CDevice<T> {
+    pub fn new(
+        i2c: T,
+        address: SevenBitAddress,
+        register_width: I2CRegisterWidth,
+    ) -> Result<I2CDevice<T>, I2CError> {
+        I2CAddress::SevenBit(address).check_valid()?;
+
+        Ok(I2CDevice {
+            i2c,
+            address,
+            register_width,
+        })
+    }
+
+    #[inline]
+    pub fn get_address(&self) -> SevenBitAddress {
+        self.address
+    }
+
+    #[inline]
+    pub fn get_register_width(&self) -> I2CRegisterWidth {
+        self.register_width
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.i2c
+    }
+
+    pub fn read_burst(&mut self, register: u16, buffer: &mut [u8]) -> Result<(), I2CError> {
+        let max_length = match self.read_register(register, buffer) {
+            Err(I2CError::ReadAfterWriteTooLong { max_length }) => usize::max(max_length, 1),
+            result => return result,
+        };
+
+        // A read longer than one report would continue in a new transfer after a stop, so every
+        // chunk addresses its register again.
+        for (index, chunk) in buffer.chunks_mut(max_length).enumerate() {
+            let chunk_register = u16::try_from(index * max_length)
+                .ok()
+                .and_then(|x| register.checked_add(x))
+                .ok_or(I2CError::InvalidRegister)?;
+
+            self.read_register(chunk_register, chunk)?;
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn write_burst(&mut self, register: u16, buffer: &[u8]) -> Result<(), I2CError> {
+        let mut bytes = self.register_width.get_register_bytes(register)?;
+
+        bytes.extend_from_slice(buffer);
+
+        self.i2c.write(self.address, &bytes)
+    }
+
+    #[inline]
+    pub fn read_reg_u8(&mut self, register: u16) -> Result<u8, I2CError> {
+        let mut buffer = [0u8; 1];
+
+        self.read_burst(register, &mut buffer)?;
+
+        Ok(buffer[0])
+    }
+
+    #[inline]
+    pub fn read_reg_u16_be(&mut self, register: u16) -> Result<u16, I2CError> {
+        let mut buffer = [0u8; 2];
+
+        self.read_burst(register, &mut buffer)?;
+
+        Ok(u16::from_be_bytes(buffer))
+    }
+
+    #[inline]
+    pub fn read_reg_u16_le(&mut self, register: u16) -> Result<u16, I2CError> {
+        let mut buffer = [0u8; 2];
+
+        self.read_burst(register, &mut buffer)?;
+
+        Ok(u16::from_le_bytes(buffer))
+    }
+
+    #[inline]
+    pub fn write_reg(&mut self, register: u16, value: u8) -> Result<(), I2CError> {
+        self.write_burst(register, &[value])
+    }
+
+    pub fn update_bits(&mut self, register: u16, mask: u8, value: u8) -> Result<(), I2CError> {
+        let old_value = self.read_reg_u8(register)?;
+        let new_value = (old_value & !mask) | (value & mask);
+
+        // Registers are only written when their content changes.
+        match new_value == old_value {
+            true => Ok(()),
+            false => self.write_reg(register, new_value),
+        }
+    }
+
+    fn read_register(&mut self, register: u16, buffer: &mut [u8]) -> Result<(), I2CError> {
+        let register_bytes = self.register_width.get_register_bytes(register)?;
+
+        self.i2c.write_read(self.address, &register_bytes, buffer)
+    }
+}
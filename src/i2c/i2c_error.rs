@@ -1,3 +1,4 @@
+use crate::iowarrior::ReportId;
 use embedded_hal::i2c::NoAcknowledgeSource;
 use hidapi::HidError;
 use thiserror::Error;
@@ -9,6 +10,8 @@ pub enum I2CError {
     ErrorUSB(HidError),
     #[error("Invalid 7 bit I2C address.")]
     InvalidAddress,
+    #[error("Invalid 10 bit I2C address.")]
+    InvalidTenBitAddress,
     #[error("I2C slave does not acknowledge command byte.")]
     NoAcknowledge,
     #[error("I2C arbitration lost.")]
@@ -21,6 +24,18 @@ pub enum I2CError {
     NackReceived,
     #[error("I2C bus error.")]
     BusError,
+    #[error("I2C repeated start after a read is not supported by hardware.")]
+    RepeatedStartNotSupported,
+    #[error("Timeout while waiting for I2C transfer.")]
+    Timeout,
+    #[error("I2C expected report {expected}, got {got:#04x}.")]
+    ProtocolError { expected: ReportId, got: u8 },
+    #[error("I2C bus recovery is not supported by hardware.")]
+    RecoveryNotSupported,
+    #[error("I2C register does not fit into register width.")]
+    InvalidRegister,
+    #[error("I2C read after a write does not fit into a single report of {max_length} bytes.")]
+    ReadAfterWriteTooLong { max_length: usize },
 }
 
 impl embedded_hal::i2c::Error for I2CError {
@@ -32,10 +47,17 @@ impl embedded_hal::i2c::Error for I2CError {
             }
             I2CError::ArbitrationLoss => embedded_hal::i2c::ErrorKind::ArbitrationLoss,
             I2CError::InvalidAddress => embedded_hal::i2c::ErrorKind::Other,
+            I2CError::InvalidTenBitAddress => embedded_hal::i2c::ErrorKind::Other,
             I2CError::BusError => embedded_hal::i2c::ErrorKind::Bus,
             I2CError::WrongAmountOfBytesRequested => embedded_hal::i2c::ErrorKind::Other,
             I2CError::TransactionWithoutStartRequested => embedded_hal::i2c::ErrorKind::Bus,
             I2CError::NackReceived => embedded_hal::i2c::ErrorKind::Bus,
+            I2CError::RepeatedStartNotSupported => embedded_hal::i2c::ErrorKind::Other,
+            I2CError::Timeout => embedded_hal::i2c::ErrorKind::Other,
+            I2CError::ProtocolError { .. } => embedded_hal::i2c::ErrorKind::Other,
+            I2CError::RecoveryNotSupported => embedded_hal::i2c::ErrorKind::Other,
+            I2CError::InvalidRegister => embedded_hal::i2c::ErrorKind::Other,
+            I2CError::ReadAfterWriteTooLong { .. } => embedded_hal::i2c::ErrorKind::Other,
         }
     }
 }
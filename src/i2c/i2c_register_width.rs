@@ -0,0 +1,27 @@
+use crate::i2c::I2CError;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum I2CRegisterWidth {
+    Bits8,
+    Bits16,
+}
+
+impl fmt::Display for I2CRegisterWidth {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl I2CRegisterWidth {
+    pub(crate) fn get_register_bytes(&self, register: u16) -> Result<Vec<u8>, I2CError> {
+        match self {
+            I2CRegisterWidth::Bits8 => match u8::try_from(register) {
+                Ok(register) => Ok(vec![register]),
+                Err(_) => Err(I2CError::InvalidRegister),
+            },
+            // Wide register addresses are sent high byte first.
+            I2CRegisterWidth::Bits16 => Ok(register.to_be_bytes().to_vec()),
+        }
+    }
+}
@@ -1,20 +1,22 @@
-use crate::bits::Bit::{Bit0, Bit6, Bit7};
-use crate::bits::Bitmasking;
+use crate::bits::Bit::{Bit6, Bit7};
+use crate::bits::{Bit, Bitmasking};
 use crate::communication::communication_service;
-use crate::i2c::{I2CConfig, I2CError, I2C};
+use crate::i2c::{I2CAddress, I2CConfig, I2CError, I2C};
 use crate::iowarrior::{
     peripheral_service, IOWarriorMutData, IOWarriorType, Peripheral, PeripheralSetupError, Pipe,
+    Shared, SharedCell, SharedRefMut,
 };
 use crate::iowarrior::{IOWarriorData, Report, ReportId};
 use crate::pin;
+use embedded_hal::digital::PinState;
+use embedded_hal::i2c::SevenBitAddress;
 use hidapi::HidError;
-use std::cell::{RefCell, RefMut};
-use std::iter;
-use std::rc::Rc;
+
+const I2C_REPORT_IDS: [ReportId; 2] = [ReportId::I2cWrite, ReportId::I2cRead];
 
 pub fn new(
-    data: &Rc<IOWarriorData>,
-    mut_data_refcell: &Rc<RefCell<IOWarriorMutData>>,
+    data: &Shared<IOWarriorData>,
+    mut_data_refcell: &Shared<SharedCell<IOWarriorMutData>>,
     i2c_config: I2CConfig,
 ) -> Result<I2C, PeripheralSetupError> {
     let mut mut_data = mut_data_refcell.borrow_mut();
@@ -52,9 +54,105 @@ fn get_i2c_pins(device_type: IOWarriorType) -> Vec<u8> {
     }
 }
 
+pub fn set_config(
+    data: &IOWarriorData,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
+    i2c_config: I2CConfig,
+) -> Result<(), I2CError> {
+    let i2c_pins = get_i2c_pins(data.device_type);
+
+    send_enable_i2c(data, mut_data, &i2c_config, &i2c_pins).map_err(I2CError::ErrorUSB)
+}
+
+pub fn recover_bus(
+    data: &IOWarriorData,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
+    i2c_config: &I2CConfig,
+) -> Result<(), I2CError> {
+    match data.device_type {
+        // IOW100 I2C pins are not part of the GPIO report.
+        IOWarriorType::IOWarrior28Dongle
+        | IOWarriorType::IOWarrior56Dongle
+        | IOWarriorType::IOWarrior100 => {
+            return Err(I2CError::RecoveryNotSupported);
+        }
+        IOWarriorType::IOWarrior40
+        | IOWarriorType::IOWarrior24
+        | IOWarriorType::IOWarrior24PowerVampire
+        | IOWarriorType::IOWarrior28
+        | IOWarriorType::IOWarrior28L
+        | IOWarriorType::IOWarrior56 => {}
+    }
+
+    let i2c_pins = get_i2c_pins(data.device_type);
+    let (scl_pin, sda_pin) = (i2c_pins[0], i2c_pins[1]);
+
+    peripheral_service::send_disable_i2c(data, &mut mut_data.communication_data)
+        .map_err(I2CError::ErrorUSB)?;
+
+    discard_reports(data, mut_data)?;
+
+    let mut pin_sequence = Vec::new();
+
+    for _ in 0..9 {
+        pin_sequence.push((scl_pin, PinState::Low));
+        pin_sequence.push((scl_pin, PinState::High));
+    }
+
+    // Stop condition, SDA rises while SCL is high.
+    pin_sequence.push((scl_pin, PinState::Low));
+    pin_sequence.push((sda_pin, PinState::Low));
+    pin_sequence.push((scl_pin, PinState::High));
+    pin_sequence.push((sda_pin, PinState::High));
+
+    // The pins are written directly, a batch would defer the clock pulses and collapse them.
+    let mut report = match &mut_data.pins_batch_report {
+        None => mut_data.pins_write_report.clone(),
+        Some(x) => x.clone(),
+    };
+
+    for (pin, pin_state) in pin_sequence {
+        report.buffer[((pin as usize) / 8usize) + 1]
+            .set_bit(Bit::from_pin(pin), bool::from(pin_state));
+
+        communication_service::write_report(&mut mut_data.communication_data, &report)
+            .map_err(I2CError::ErrorUSB)?;
+    }
+
+    send_enable_i2c(data, mut_data, i2c_config, &i2c_pins).map_err(I2CError::ErrorUSB)
+}
+
+// Late responses of timed out transfers are dropped, so they are not taken for later ones.
+// Reports of other peripherals on the same interface are kept.
+fn drain_late_responses(
+    data: &IOWarriorData,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
+) -> Result<(), I2CError> {
+    match mut_data.i2c_response_pending {
+        true => discard_reports(data, mut_data),
+        false => Ok(()),
+    }
+}
+
+fn discard_reports(
+    data: &IOWarriorData,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
+) -> Result<(), I2CError> {
+    communication_service::discard_reports(
+        &mut mut_data.communication_data,
+        data.create_report(Pipe::I2CMode),
+        &I2C_REPORT_IDS,
+    )
+    .map_err(I2CError::ErrorUSB)?;
+
+    mut_data.i2c_response_pending = false;
+
+    Ok(())
+}
+
 fn send_enable_i2c(
     data: &IOWarriorData,
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
     i2c_config: &I2CConfig,
     i2c_pins: &Vec<u8>,
 ) -> Result<(), HidError> {
@@ -82,14 +180,35 @@ fn send_enable_i2c(
 }
 
 pub fn write_data(
-    data: &Rc<IOWarriorData>,
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    data: &IOWarriorData,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
+    i2c_config: &I2CConfig,
     address: u8,
     buffer: &[u8],
 ) -> Result<(), I2CError> {
-    check_valid_7bit_address(address)?;
+    let address = I2CAddress::SevenBit(address);
 
-    let chunk_iterator = buffer.chunks(data.special_report_size - 3);
+    address.check_valid()?;
+
+    drain_late_responses(data, mut_data)?;
+
+    write_chunks(data, mut_data, i2c_config, address, buffer, true)
+}
+
+fn write_chunks(
+    data: &IOWarriorData,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
+    i2c_config: &I2CConfig,
+    address: I2CAddress,
+    buffer: &[u8],
+    generate_stop: bool,
+) -> Result<(), I2CError> {
+    // Only the first chunk starts the transfer with the address, the following chunks continue it.
+    let mut bytes = address.get_write_header();
+
+    bytes.extend_from_slice(buffer);
+
+    let chunk_iterator = bytes.chunks(data.special_report_size - 2);
     let chunk_iterator_count = chunk_iterator.len();
 
     let report_id = ReportId::I2cWrite;
@@ -101,14 +220,14 @@ pub fn write_data(
 
     for (index, chunk) in chunk_iterator.enumerate() {
         let start_byte = index == 0;
-        let stop_byte = index == chunk_iterator_count - 1;
+        let stop_byte = generate_stop && index == chunk_iterator_count - 1;
 
         report.buffer.clear();
 
         report.buffer.push(report_id.get_value());
 
         report.buffer.push({
-            let mut value = (chunk.len() + 1) as u8;
+            let mut value = chunk.len() as u8;
 
             value.set_bit(Bit6, stop_byte);
             value.set_bit(Bit7, start_byte);
@@ -116,62 +235,68 @@ pub fn write_data(
             value
         });
 
-        report.buffer.push({
-            let mut value = address << 1;
-
-            value.set_bit(Bit0, false); // Write address
-
-            value
-        });
-
         report.buffer.extend(chunk);
-        report
-            .buffer
-            .extend(iter::repeat(0u8).take(data.special_report_size - report.buffer.len()));
+        report.buffer.resize(data.special_report_size, 0u8);
 
         communication_service::write_report(&mut mut_data.communication_data, &report)
-            .map_err(|x| I2CError::ErrorUSB(x))?;
-    }
+            .map_err(I2CError::ErrorUSB)?;
 
-    _ = read_report(data, mut_data, report_id)?;
+        _ = read_report(data, mut_data, i2c_config, report_id)?;
+    }
 
     Ok(())
 }
 
 pub fn read_data(
     data: &IOWarriorData,
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
+    i2c_config: &I2CConfig,
     address: u8,
     buffer: &mut [u8],
 ) -> Result<(), I2CError> {
-    check_valid_7bit_address(address)?;
+    let address = I2CAddress::SevenBit(address);
+
+    address.check_valid()?;
+
+    drain_late_responses(data, mut_data)?;
+
+    read_chunks(data, mut_data, i2c_config, address, buffer, false)
+}
 
+fn read_chunks(
+    data: &IOWarriorData,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
+    i2c_config: &I2CConfig,
+    address: I2CAddress,
+    buffer: &mut [u8],
+    is_addressed: bool,
+) -> Result<(), I2CError> {
     let chunk_iterator = buffer.chunks_mut(data.special_report_size - 2);
     let report_id = ReportId::I2cRead;
 
-    for chunk in chunk_iterator {
+    for (index, chunk) in chunk_iterator.enumerate() {
         let chunk_length = chunk.len() as u8;
 
+        // A 10 bit read header is only valid after the full address was sent without stop.
+        if let I2CAddress::TenBit(_) = address {
+            if index > 0 || !is_addressed {
+                write_chunks(data, mut_data, i2c_config, address, &[], false)?;
+            }
+        }
+
         {
             let mut report = data.create_report(Pipe::I2CMode);
 
             report.buffer[0] = report_id.get_value();
             report.buffer[1] = chunk_length;
-
-            report.buffer[2] = {
-                let mut value = address << 1;
-
-                value.set_bit(Bit0, true); // Read address
-
-                value
-            };
+            report.buffer[2] = address.get_read_header();
 
             communication_service::write_report(&mut mut_data.communication_data, &report)
-                .map_err(|x| I2CError::ErrorUSB(x))?;
+                .map_err(I2CError::ErrorUSB)?;
         }
 
         {
-            let report = read_report(data, mut_data, report_id)?;
+            let report = read_report(data, mut_data, i2c_config, report_id)?;
 
             chunk.copy_from_slice(&report.buffer[2..((chunk_length + 2) as usize)]);
         }
@@ -180,18 +305,167 @@ pub fn read_data(
     Ok(())
 }
 
+pub fn transaction(
+    data: &IOWarriorData,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
+    i2c_config: &I2CConfig,
+    address: I2CAddress,
+    operations: &mut [embedded_hal::i2c::Operation],
+) -> Result<(), I2CError> {
+    address.check_valid()?;
+
+    // Adjacent operations of the same kind are merged.
+    // A write before a read ends without stop, so the read starts with a repeated start.
+    let mut groups: Vec<&mut [embedded_hal::i2c::Operation]> = Vec::new();
+    let mut remaining = operations;
+
+    while !remaining.is_empty() {
+        let is_read = matches!(remaining[0], embedded_hal::i2c::Operation::Read(_));
+
+        let group_length = remaining
+            .iter()
+            .take_while(|x| matches!(x, embedded_hal::i2c::Operation::Read(_)) == is_read)
+            .count();
+
+        let (group, rest) = remaining.split_at_mut(group_length);
+
+        if is_read && !rest.is_empty() {
+            return Err(I2CError::RepeatedStartNotSupported);
+        }
+
+        // An empty read transfers nothing, so the preceding write has to send the stop.
+        if !is_read || get_read_length(group) > 0 {
+            groups.push(group);
+        }
+
+        remaining = rest;
+    }
+
+    if let Some(last_group) = groups.last() {
+        if let embedded_hal::i2c::Operation::Read(_) = last_group[0] {
+            let is_write_read = groups.len() > 1;
+
+            // Every read report ends with a stop, a longer read would continue in a new transfer.
+            if is_write_read && get_read_length(last_group) > data.special_report_size - 2 {
+                return Err(I2CError::ReadAfterWriteTooLong {
+                    max_length: data.special_report_size - 2,
+                });
+            }
+        }
+    }
+
+    drain_late_responses(data, mut_data)?;
+
+    let group_count = groups.len();
+
+    for (index, group) in groups.into_iter().enumerate() {
+        match group[0] {
+            embedded_hal::i2c::Operation::Write(_) => {
+                let mut bytes = Vec::new();
+
+                for operation in group.iter() {
+                    if let embedded_hal::i2c::Operation::Write(buffer) = operation {
+                        bytes.extend_from_slice(buffer);
+                    }
+                }
+
+                write_chunks(
+                    data,
+                    mut_data,
+                    i2c_config,
+                    address,
+                    &bytes,
+                    index == group_count - 1,
+                )?;
+            }
+            embedded_hal::i2c::Operation::Read(_) => {
+                let mut bytes = vec![0u8; get_read_length(group)];
+
+                read_chunks(data, mut_data, i2c_config, address, &mut bytes, index > 0)?;
+
+                let mut offset = 0;
+
+                for operation in group.iter_mut() {
+                    if let embedded_hal::i2c::Operation::Read(buffer) = operation {
+                        buffer.copy_from_slice(&bytes[offset..(offset + buffer.len())]);
+                        offset += buffer.len();
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn get_read_length(operations: &[embedded_hal::i2c::Operation]) -> usize {
+    operations
+        .iter()
+        .map(|x| match x {
+            embedded_hal::i2c::Operation::Read(buffer) => buffer.len(),
+            embedded_hal::i2c::Operation::Write(_) => 0,
+        })
+        .sum()
+}
+
+pub fn scan(
+    data: &IOWarriorData,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
+    i2c_config: &I2CConfig,
+) -> Result<Vec<SevenBitAddress>, I2CError> {
+    let mut addresses = Vec::new();
+    let mut buffer = [0u8; 1];
+
+    for address in (0..=127).filter(|x| I2CAddress::SevenBit(*x).check_valid().is_ok()) {
+        match read_data(data, mut_data, i2c_config, address, &mut buffer) {
+            Ok(_) => addresses.push(address),
+            Err(I2CError::NoAcknowledge | I2CError::NackReceived) => {}
+            Err(error) => return Err(error),
+        }
+    }
+
+    Ok(addresses)
+}
+
 fn read_report(
     data: &IOWarriorData,
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
+    i2c_config: &I2CConfig,
     report_id: ReportId,
 ) -> Result<Report, I2CError> {
-    let report = communication_service::read_report(
-        &mut mut_data.communication_data,
-        data.create_report(Pipe::I2CMode),
-    )
-    .map_err(|x| I2CError::ErrorUSB(x))?;
+    let report = match i2c_config.timeout {
+        None => communication_service::read_report_by_id(
+            &mut mut_data.communication_data,
+            data.create_report(Pipe::I2CMode),
+            &I2C_REPORT_IDS,
+        )
+        .map(Some),
+        Some(timeout) => communication_service::read_report_by_id_timeout(
+            &mut mut_data.communication_data,
+            data.create_report(Pipe::I2CMode),
+            &I2C_REPORT_IDS,
+            timeout,
+        ),
+    }
+    .map_err(I2CError::ErrorUSB)?;
+
+    let report = match report {
+        None => {
+            mut_data.i2c_response_pending = true;
+
+            return Err(I2CError::Timeout);
+        }
+        Some(x) => x,
+    };
 
-    assert_eq!(report.buffer[0], report_id.get_value());
+    if report.buffer[0] != report_id.get_value() {
+        mut_data.i2c_response_pending = true;
+
+        return Err(I2CError::ProtocolError {
+            expected: report_id,
+            got: report.buffer[0],
+        });
+    }
 
     if report.buffer[1].get_bit(Bit7) {
         return Err(I2CError::NoAcknowledge);
@@ -235,14 +509,3 @@ fn read_report(
 
     Ok(report)
 }
-
-fn check_valid_7bit_address(address: u8) -> Result<(), I2CError> {
-    if address > 127 {
-        return Err(I2CError::InvalidAddress);
-    }
-
-    match address > 0 && !(address >= 0x78 && address <= 0x7F) {
-        true => Ok(()),
-        false => Err(I2CError::InvalidAddress),
-    }
-}
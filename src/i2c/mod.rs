@@ -1,8 +1,18 @@
 mod i2c;
+mod i2c_address;
+#[cfg(feature = "embedded-hal-async")]
+mod i2c_async;
 mod i2c_config;
+mod i2c_device;
 mod i2c_error;
+mod i2c_register_width;
 pub(crate) mod i2c_service;
+mod shared_i2c;
 
 pub use self::i2c::*;
+pub(crate) use self::i2c_address::*;
 pub use self::i2c_config::*;
+pub use self::i2c_device::*;
 pub use self::i2c_error::*;
+pub use self::i2c_register_width::*;
+pub use self::shared_i2c::*;
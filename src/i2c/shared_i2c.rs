@@ -0,0 +1,104 @@
+use crate::i2c::{I2CConfig, I2CError, I2C};
+use crate::iowarrior::{Shared, SharedCell};
+use embedded_hal::i2c::SevenBitAddress;
+use std::fmt;
+
+#[derive(Clone, Debug)]
+pub struct SharedI2C {
+    pub(crate) i2c: Shared<SharedCell<I2C>>,
+}
+
+impl fmt::Display for SharedI2C {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl embedded_hal::i2c::ErrorType for SharedI2C {
+    type Error = I2CError;
+}
+
+impl embedded_hal::i2c::I2c<embedded_hal::i2c::SevenBitAddress> for SharedI2C {
+    #[inline]
+    fn transaction(
+        &mut self,
+        address: embedded_hal::i2c::SevenBitAddress,
+        operations: &mut [embedded_hal::i2c::Operation],
+    ) -> Result<(), Self::Error> {
+        embedded_hal::i2c::I2c::transaction(&mut *self.i2c.borrow_mut(), address, operations)
+    }
+}
+
+impl embedded_hal::i2c::I2c<embedded_hal::i2c::TenBitAddress> for SharedI2C {
+    #[inline]
+    fn transaction(
+        &mut self,
+        address: embedded_hal::i2c::TenBitAddress,
+        operations: &mut [embedded_hal::i2c::Operation],
+    ) -> Result<(), Self::Error> {
+        embedded_hal::i2c::I2c::transaction(&mut *self.i2c.borrow_mut(), address, operations)
+    }
+}
+
+#[cfg(feature = "embedded-hal-0")]
+impl embedded_hal_0::blocking::i2c::Write for SharedI2C {
+    type Error = I2CError;
+
+    #[inline]
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        embedded_hal_0::blocking::i2c::Write::write(&mut *self.i2c.borrow_mut(), address, bytes)
+    }
+}
+
+#[cfg(feature = "embedded-hal-0")]
+impl embedded_hal_0::blocking::i2c::Read for SharedI2C {
+    type Error = I2CError;
+
+    #[inline]
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        embedded_hal_0::blocking::i2c::Read::read(&mut *self.i2c.borrow_mut(), address, buffer)
+    }
+}
+
+#[cfg(feature = "embedded-hal-0")]
+impl embedded_hal_0::blocking::i2c::WriteRead for SharedI2C {
+    type Error = I2CError;
+
+    #[inline]
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        embedded_hal_0::blocking::i2c::WriteRead::write_read(
+            &mut *self.i2c.borrow_mut(),
+            address,
+            bytes,
+            buffer,
+        )
+    }
+}
+
+impl SharedI2C {
+    #[inline]
+    pub fn get_config(&self) -> I2CConfig {
+        self.i2c.borrow_mut().get_config()
+    }
+
+    // The config is shared, a change applies to every clone.
+    #[inline]
+    pub fn set_config(&mut self, i2c_config: I2CConfig) -> Result<(), I2CError> {
+        self.i2c.borrow_mut().set_config(i2c_config)
+    }
+
+    #[inline]
+    pub fn scan(&mut self) -> Result<Vec<SevenBitAddress>, I2CError> {
+        self.i2c.borrow_mut().scan()
+    }
+
+    #[inline]
+    pub fn recover_bus(&mut self) -> Result<(), I2CError> {
+        self.i2c.borrow_mut().recover_bus()
+    }
+}
@@ -1,19 +1,25 @@
 use crate::adc::{adc_service, ADCConfig, ADC};
-use crate::digital::{digital_service, InputPin, OutputPin, PinSetupError};
+use crate::communication::ReconnectData;
+use crate::digital::{
+    digital_service, Debounce, DebouncedInput, FlexPin, GpioPort, InputPin, OutputPin, PinError,
+    PinSetupError,
+};
+#[cfg(feature = "sync")]
+use crate::digital::{PinMonitor, WaveformPlayer};
 use crate::i2c::{i2c_service, I2CConfig, I2C};
-use crate::iowarrior::{IOWarriorData, IOWarriorMutData};
+use crate::iowarrior::{peripheral_service, IOWarriorData, IOWarriorMutData, Shared, SharedCell};
 use crate::iowarrior::{IOWarriorType, PeripheralSetupError};
+use crate::ir::{ir_service, IRReceiver};
 use crate::pwm::{pwm_service, PWMConfig, PWM};
 use crate::spi::{spi_service, SPIConfig, SPI};
 use embedded_hal::digital::PinState;
-use std::cell::RefCell;
 use std::fmt;
-use std::rc::Rc;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub struct IOWarrior {
-    pub(crate) data: Rc<IOWarriorData>,
-    pub(crate) mut_data_refcell: Rc<RefCell<IOWarriorMutData>>,
+    pub(crate) data: Shared<IOWarriorData>,
+    pub(crate) mut_data_refcell: Shared<SharedCell<IOWarriorMutData>>,
 }
 
 impl fmt::Display for IOWarrior {
@@ -98,6 +104,11 @@ impl IOWarrior {
         spi_service::new(&self.data, &self.mut_data_refcell, spi_config)
     }
 
+    #[inline]
+    pub fn setup_ir_receiver(&self) -> Result<IRReceiver, PeripheralSetupError> {
+        ir_service::new(&self.data, &self.mut_data_refcell)
+    }
+
     #[inline]
     pub fn setup_output_as_high(&self, pin: u8) -> Result<OutputPin, PinSetupError> {
         digital_service::new_output(&self.data, &self.mut_data_refcell, PinState::High, pin)
@@ -112,4 +123,98 @@ impl IOWarrior {
     pub fn setup_input(&self, pin: u8) -> Result<InputPin, PinSetupError> {
         digital_service::new_input(&self.data, &self.mut_data_refcell, pin)
     }
+
+    #[inline]
+    pub fn setup_debounced_input(
+        &self,
+        pin: u8,
+        debounce: Debounce,
+    ) -> Result<DebouncedInput, PinSetupError> {
+        digital_service::new_debounced_input(&self.data, &self.mut_data_refcell, pin, debounce)
+    }
+
+    #[inline]
+    pub fn setup_flex_pin(&self, pin: u8) -> Result<FlexPin, PinSetupError> {
+        digital_service::new_flex(&self.data, &self.mut_data_refcell, PinState::High, pin)
+    }
+
+    #[inline]
+    pub fn setup_gpio_port(&self, pins: &[u8]) -> Result<GpioPort, PinSetupError> {
+        digital_service::new_gpio_port(&self.data, &self.mut_data_refcell, pins)
+    }
+
+    #[cfg(feature = "sync")]
+    #[inline]
+    pub fn setup_waveform_player(&self, pins: &[u8]) -> Result<WaveformPlayer, PinSetupError> {
+        digital_service::new_waveform_player(&self.data, &self.mut_data_refcell, pins)
+    }
+
+    #[cfg(feature = "sync")]
+    #[inline]
+    pub fn setup_pin_monitor(&self, pins: &[u8]) -> Result<PinMonitor, PinSetupError> {
+        digital_service::new_pin_monitor(&self.data, &self.mut_data_refcell, pins)
+    }
+
+    #[inline]
+    pub fn begin_batch(&self) {
+        peripheral_service::begin_pins_batch(&mut self.mut_data_refcell.borrow_mut());
+    }
+
+    #[inline]
+    pub fn commit_batch(&self) -> Result<(), PinError> {
+        peripheral_service::commit_pins_batch(&mut self.mut_data_refcell.borrow_mut())
+            .map_err(PinError::ErrorUSB)
+    }
+
+    #[inline]
+    pub fn batch<T>(&self, f: impl FnOnce(&IOWarrior) -> T) -> Result<T, PinError> {
+        self.begin_batch();
+
+        let mut batch_guard = BatchGuard {
+            iowarrior: self,
+            is_committed: false,
+        };
+
+        let result = f(self);
+
+        batch_guard.is_committed = true;
+
+        self.commit_batch()?;
+
+        Ok(result)
+    }
+
+    #[inline]
+    pub fn enable_reconnect(&self, timeout: Duration) {
+        self.mut_data_refcell
+            .borrow_mut()
+            .communication_data
+            .reconnect_data = Some(ReconnectData {
+            device_serial: self.data.device_serial.clone(),
+            device_type: self.data.device_type,
+            timeout,
+        });
+    }
+
+    #[inline]
+    pub fn disable_reconnect(&self) {
+        self.mut_data_refcell
+            .borrow_mut()
+            .communication_data
+            .reconnect_data = None;
+    }
+}
+
+// Ends the batch if the closure panics, otherwise the pins of the board would stay deferred.
+struct BatchGuard<'a> {
+    iowarrior: &'a IOWarrior,
+    is_committed: bool,
+}
+
+impl Drop for BatchGuard<'_> {
+    fn drop(&mut self) {
+        if !self.is_committed {
+            let _ = self.iowarrior.commit_batch();
+        }
+    }
 }
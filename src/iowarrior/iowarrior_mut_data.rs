@@ -10,6 +10,9 @@ pub struct IOWarriorMutData {
     pub dangling_peripherals: Vec<Peripheral>,
     pub pins_write_report: Report,
     pub pins_read_report: Report,
+    pub pins_batch_depth: usize,
+    pub pins_batch_report: Option<Report>,
+    pub i2c_response_pending: bool,
 }
 
 impl fmt::Display for IOWarriorMutData {
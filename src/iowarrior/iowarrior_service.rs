@@ -1,17 +1,29 @@
-use crate::communication::{communication_service, CommunicationData};
+use crate::communication::{communication_service, Backend, CommunicationData, Transport};
 use crate::iowarrior::{
-    IOWarrior, IOWarriorData, IOWarriorMutData, IOWarriorType, Pipe, Report, ReportId,
+    IOWarrior, IOWarriorData, IOWarriorMutData, IOWarriorType, Pipe, Report, ReportId, Shared,
+    SharedCell,
 };
 use hidapi::HidError;
-use std::cell::RefCell;
-use std::rc::Rc;
+use std::collections::VecDeque;
 
 pub fn create_iowarrior(
     device_type: IOWarriorType,
     device_revision: u16,
     device_serial: String,
-    mut communication_data: CommunicationData,
+    transport: Box<dyn Transport>,
 ) -> Result<IOWarrior, HidError> {
+    let mut communication_data = CommunicationData {
+        transport,
+        backend: None,
+        device_path: None,
+        reconnect_data: None,
+        replay_reports: vec![],
+        disconnected_since: None,
+        last_reconnect_attempt: None,
+        pending_pins_report: None,
+        queued_reports: VecDeque::new(),
+    };
+
     let mut data = IOWarriorData {
         device_serial,
         device_revision,
@@ -28,23 +40,46 @@ pub fn create_iowarrior(
         data.device_type = get_iowarrior28_subtype(&data, &mut communication_data)?;
     }
 
-    let pins_report = get_pins_report(&data, &mut communication_data)?;
+    let pins_report = get_pins_report(&data, communication_data.transport.as_mut())?;
 
     let mut_data = IOWarriorMutData {
         pins_in_use: vec![],
         dangling_peripherals: vec![],
         pins_write_report: pins_report.clone(),
         pins_read_report: pins_report,
+        pins_batch_depth: 0,
+        pins_batch_report: None,
+        i2c_response_pending: false,
         communication_data,
     };
 
     Ok(IOWarrior {
-        data: Rc::new(data),
-        mut_data_refcell: Rc::new(RefCell::new(mut_data)),
+        data: Shared::new(data),
+        mut_data_refcell: Shared::new(SharedCell::new(mut_data)),
     })
 }
 
-fn get_standard_report_size(device_type: IOWarriorType) -> usize {
+// The backend and device path are kept to reopen the same board after a disconnect.
+pub(crate) fn set_device_origin(
+    iowarrior: &IOWarrior,
+    backend: Backend,
+    device_path: Option<String>,
+) {
+    let mut mut_data = iowarrior.mut_data_refcell.borrow_mut();
+
+    mut_data.communication_data.backend = Some(backend);
+    mut_data.communication_data.device_path = device_path;
+}
+
+pub fn into_transport(iowarrior: IOWarrior) -> Option<(Shared<IOWarriorData>, Box<dyn Transport>)> {
+    let data = iowarrior.data.clone();
+
+    Shared::try_unwrap(iowarrior.mut_data_refcell)
+        .ok()
+        .map(|x| (data, x.into_inner().communication_data.transport))
+}
+
+pub(crate) fn get_standard_report_size(device_type: IOWarriorType) -> usize {
     match device_type {
         IOWarriorType::IOWarrior24 | IOWarriorType::IOWarrior24PowerVampire => 3,
         IOWarriorType::IOWarrior28
@@ -57,7 +92,7 @@ fn get_standard_report_size(device_type: IOWarriorType) -> usize {
     }
 }
 
-fn get_special_report_size(device_type: IOWarriorType) -> usize {
+pub(crate) fn get_special_report_size(device_type: IOWarriorType) -> usize {
     match device_type {
         IOWarriorType::IOWarrior40
         | IOWarriorType::IOWarrior24
@@ -123,23 +158,20 @@ fn get_iowarrior28_subtype(
     }
 }
 
-fn get_pins_report(
+pub fn get_pins_report(
     data: &IOWarriorData,
-    communication_data: &mut CommunicationData,
+    transport: &mut dyn Transport,
 ) -> Result<Report, HidError> {
     {
         let mut report = data.create_report(Pipe::SpecialMode);
 
         report.buffer[0] = ReportId::GpioSpecialRead.get_value();
 
-        communication_service::write_report(communication_data, &report)?;
+        transport.write_report(&report)?;
     }
 
     {
-        let mut report = communication_service::read_report(
-            communication_data,
-            data.create_report(Pipe::SpecialMode),
-        )?;
+        let mut report = transport.read_report(data.create_report(Pipe::SpecialMode))?;
 
         report.buffer[0] = ReportId::GpioReadWrite.get_value();
 
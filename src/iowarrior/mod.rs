@@ -9,15 +9,22 @@ mod peripheral_setup_error;
 mod pipe;
 mod report;
 mod report_id;
+mod shared_cell;
+#[cfg(feature = "embedded-hal-async")]
+mod unblock;
 mod used_pin;
 
 pub use self::iowarrior::*;
 pub(crate) use self::iowarrior_data::*;
 pub(crate) use self::iowarrior_mut_data::*;
+pub use self::iowarrior_service::create_iowarrior;
 pub use self::iowarrior_type::*;
 pub use self::peripheral::*;
 pub use self::peripheral_setup_error::*;
-pub(crate) use self::pipe::*;
-pub(crate) use self::report::*;
-pub(crate) use self::report_id::*;
+pub use self::pipe::*;
+pub use self::report::*;
+pub use self::report_id::*;
+pub(crate) use self::shared_cell::*;
+#[cfg(feature = "embedded-hal-async")]
+pub(crate) use self::unblock::*;
 pub(crate) use self::used_pin::*;
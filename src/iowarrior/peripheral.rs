@@ -6,6 +6,7 @@ pub enum Peripheral {
     PWM,
     SPI,
     ADC,
+    IR,
 }
 
 impl fmt::Display for Peripheral {
@@ -2,14 +2,14 @@ use crate::bits::Bit;
 use crate::bits::Bitmasking;
 use crate::communication::{communication_service, CommunicationData};
 use crate::iowarrior::{
-    IOWarriorData, IOWarriorMutData, Peripheral, PeripheralSetupError, Pipe, ReportId, UsedPin,
+    IOWarriorData, IOWarriorMutData, Peripheral, PeripheralSetupError, Pipe, ReportId,
+    SharedRefMut, UsedPin,
 };
 use embedded_hal::digital::PinState;
 use hidapi::HidError;
-use std::cell::RefMut;
 
 pub fn get_used_pins(
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
     peripheral: Peripheral,
 ) -> Vec<UsedPin> {
     mut_data
@@ -22,7 +22,7 @@ pub fn get_used_pins(
 
 pub fn precheck_peripheral(
     data: &IOWarriorData,
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
     peripheral: Peripheral,
     required_pins: &Vec<u8>,
 ) -> Result<(), PeripheralSetupError> {
@@ -53,7 +53,7 @@ pub fn precheck_peripheral(
 }
 
 pub fn post_enable(
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
     peripheral_pins: &Vec<u8>,
     peripheral: Peripheral,
 ) {
@@ -67,7 +67,7 @@ pub fn post_enable(
 
 pub fn cleanup_dangling_modules(
     data: &IOWarriorData,
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
 ) -> Result<(), HidError> {
     if !mut_data.dangling_peripherals.is_empty() {
         for x in mut_data.dangling_peripherals.to_vec() {
@@ -76,6 +76,7 @@ pub fn cleanup_dangling_modules(
                 Peripheral::PWM => send_disable_pwm(&data, &mut mut_data.communication_data),
                 Peripheral::SPI => send_disable_spi(&data, &mut mut_data.communication_data),
                 Peripheral::ADC => send_disable_adc(&data, &mut mut_data.communication_data),
+                Peripheral::IR => send_disable_ir(data, &mut mut_data.communication_data),
             }?;
 
             mut_data.dangling_peripherals.retain(|y| *y != x);
@@ -87,16 +88,36 @@ pub fn cleanup_dangling_modules(
 
 pub fn set_pin_output(
     data: &IOWarriorData,
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
     pin_state: PinState,
     pin: u8,
 ) -> Result<(), HidError> {
-    let byte_index = ((pin as usize) / 8usize) + 1;
-    let bit_index = Bit::from_u8(pin % 8u8);
+    set_pins_output(data, mut_data, &[(pin, pin_state)])
+}
 
+pub fn set_pins_output(
+    data: &IOWarriorData,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
+    pin_states: &[(u8, PinState)],
+) -> Result<(), HidError> {
     let mut pins_write_report = mut_data.pins_write_report.clone();
 
-    pins_write_report.buffer[byte_index].set_bit(bit_index, bool::from(pin_state));
+    for (pin, pin_state) in pin_states {
+        let byte_index = ((*pin as usize) / 8usize) + 1;
+        let bit_index = Bit::from_pin(*pin);
+
+        pins_write_report.buffer[byte_index].set_bit(bit_index, bool::from(*pin_state));
+    }
+
+    // The batch applies to the whole board, pin changes of other handles are deferred as well.
+    if mut_data.pins_batch_depth > 0 {
+        if mut_data.pins_batch_report.is_none() {
+            mut_data.pins_batch_report = Some(mut_data.pins_write_report.clone());
+        }
+
+        mut_data.pins_write_report = pins_write_report;
+        return Ok(());
+    }
 
     match communication_service::write_report(&mut mut_data.communication_data, &pins_write_report)
     {
@@ -108,18 +129,55 @@ pub fn set_pin_output(
     }
 }
 
-pub fn disable_gpio(data: &IOWarriorData, mut_data: &mut RefMut<IOWarriorMutData>, pin: u8) {
-    match set_pin_output(&data, mut_data, PinState::High, pin) {
+pub fn begin_pins_batch(mut_data: &mut SharedRefMut<IOWarriorMutData>) {
+    mut_data.pins_batch_depth += 1;
+}
+
+pub fn commit_pins_batch(mut_data: &mut SharedRefMut<IOWarriorMutData>) -> Result<(), HidError> {
+    mut_data.pins_batch_depth = mut_data.pins_batch_depth.saturating_sub(1);
+
+    if mut_data.pins_batch_depth > 0 {
+        return Ok(());
+    }
+
+    // The report sent before the batch, kept to restore the cached state if the commit fails.
+    let pins_batch_report = match mut_data.pins_batch_report.take() {
+        None => return Ok(()),
+        Some(x) => x,
+    };
+
+    let pins_write_report = mut_data.pins_write_report.clone();
+
+    match communication_service::write_report(&mut mut_data.communication_data, &pins_write_report)
+    {
+        Ok(_) => Ok(()),
+        Err(error) => {
+            mut_data.pins_write_report = pins_batch_report;
+            Err(error)
+        }
+    }
+}
+
+pub fn disable_gpio(
+    data: &IOWarriorData,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
+    pins: &[u8],
+) {
+    let pin_states: Vec<(u8, PinState)> = pins.iter().map(|pin| (*pin, PinState::High)).collect();
+
+    match set_pins_output(data, mut_data, &pin_states) {
         Ok(_) => {}
         Err(_) => { /* Ignore error. Every following pin and peripheral can handle this. */ }
     };
 
-    mut_data.pins_in_use.retain(|x| x.pin == pin);
+    mut_data
+        .pins_in_use
+        .retain(|x| x.peripheral.is_some() || !pins.contains(&x.pin));
 }
 
 pub fn disable_peripheral(
     data: &IOWarriorData,
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
     peripheral: Peripheral,
 ) {
     match match peripheral {
@@ -127,6 +185,7 @@ pub fn disable_peripheral(
         Peripheral::PWM => send_disable_pwm(data, &mut mut_data.communication_data),
         Peripheral::SPI => send_disable_spi(data, &mut mut_data.communication_data),
         Peripheral::ADC => send_disable_adc(data, &mut mut_data.communication_data),
+        Peripheral::IR => send_disable_ir(data, &mut mut_data.communication_data),
     } {
         Ok(_) => {
             mut_data
@@ -139,7 +198,7 @@ pub fn disable_peripheral(
     }
 }
 
-fn send_disable_i2c(
+pub fn send_disable_i2c(
     data: &IOWarriorData,
     communication_data: &mut CommunicationData,
 ) -> Result<(), HidError> {
@@ -186,3 +245,15 @@ fn send_disable_adc(
 
     communication_service::write_report(communication_data, &report)
 }
+
+fn send_disable_ir(
+    data: &IOWarriorData,
+    communication_data: &mut CommunicationData,
+) -> Result<(), HidError> {
+    let mut report = data.create_report(Pipe::SpecialMode);
+
+    report.buffer[0] = ReportId::IrSetup.get_value();
+    report.buffer[1] = 0x00;
+
+    communication_service::write_report(communication_data, &report)
+}
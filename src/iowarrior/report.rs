@@ -8,7 +8,7 @@ pub struct Report {
 }
 
 impl Report {
-    pub fn new(data: &IOWarriorData, pipe: Pipe) -> Report {
+    pub(crate) fn new(data: &IOWarriorData, pipe: Pipe) -> Report {
         Report {
             buffer: match pipe {
                 Pipe::IOPins => {
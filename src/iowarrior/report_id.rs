@@ -7,6 +7,8 @@ pub enum ReportId {
     I2cSetup = 0x01,
     I2cWrite = 0x02,
     I2cRead = 0x03,
+    IrSetup = 0x0C,
+    IrRead = 0x0D,
     PwmSetup = 0x20,
     PwmParameters = 0x21,
     SpiSetup = 0x08,
@@ -29,4 +31,26 @@ impl ReportId {
     pub fn get_value(&self) -> u8 {
         *self as u8
     }
+
+    pub fn from_u8(report_id: u8) -> Option<ReportId> {
+        match report_id {
+            0x1C => Some(ReportId::AdcSetup),
+            0x1D => Some(ReportId::AdcRead),
+            0x01 => Some(ReportId::I2cSetup),
+            0x02 => Some(ReportId::I2cWrite),
+            0x03 => Some(ReportId::I2cRead),
+            0x0C => Some(ReportId::IrSetup),
+            0x0D => Some(ReportId::IrRead),
+            0x20 => Some(ReportId::PwmSetup),
+            0x21 => Some(ReportId::PwmParameters),
+            0x08 => Some(ReportId::SpiSetup),
+            0x09 => Some(ReportId::SpiTransfer),
+            0x28 => Some(ReportId::TimerSetup),
+            0x29 => Some(ReportId::TimerDataA),
+            0x2A => Some(ReportId::TimerDataB),
+            0x00 => Some(ReportId::GpioReadWrite),
+            0xFF => Some(ReportId::GpioSpecialRead),
+            _ => None,
+        }
+    }
 }
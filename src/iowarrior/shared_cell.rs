@@ -0,0 +1,151 @@
+use std::fmt;
+
+#[cfg(not(feature = "sync"))]
+pub type Shared<T> = std::rc::Rc<T>;
+
+#[cfg(feature = "sync")]
+pub type Shared<T> = std::sync::Arc<T>;
+
+#[cfg(not(feature = "sync"))]
+pub type SharedRefMut<'a, T> = std::cell::RefMut<'a, T>;
+
+#[derive(Debug)]
+pub struct SharedCell<T> {
+    #[cfg(not(feature = "sync"))]
+    inner: std::cell::RefCell<T>,
+    #[cfg(feature = "sync")]
+    inner: std::sync::Mutex<T>,
+    #[cfg(feature = "sync")]
+    tickets: std::sync::Mutex<Tickets>,
+    #[cfg(feature = "sync")]
+    tickets_condvar: std::sync::Condvar,
+}
+
+impl<T: fmt::Debug> fmt::Display for SharedCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl<T> SharedCell<T> {
+    #[inline]
+    pub fn new(value: T) -> SharedCell<T> {
+        SharedCell {
+            inner: value.into(),
+            #[cfg(feature = "sync")]
+            tickets: std::sync::Mutex::new(Tickets::default()),
+            #[cfg(feature = "sync")]
+            tickets_condvar: std::sync::Condvar::new(),
+        }
+    }
+
+    #[cfg(not(feature = "sync"))]
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.inner.into_inner()
+    }
+
+    #[cfg(feature = "sync")]
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.inner
+            .into_inner()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    #[cfg(not(feature = "sync"))]
+    #[inline]
+    pub fn borrow_mut(&self) -> SharedRefMut<'_, T> {
+        self.inner.borrow_mut()
+    }
+
+    // Threads get the lock in the order they asked for it. Handles waiting in slices release
+    // and request the lock again, a plain mutex would let them starve the other handles.
+    #[cfg(feature = "sync")]
+    pub fn borrow_mut(&self) -> SharedRefMut<'_, T> {
+        let mut tickets = self
+            .tickets
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        let ticket = tickets.next_ticket;
+
+        tickets.next_ticket = tickets.next_ticket.wrapping_add(1);
+
+        while tickets.serving_ticket != ticket {
+            tickets = self
+                .tickets_condvar
+                .wait(tickets)
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+        }
+
+        drop(tickets);
+
+        // A panic while holding the lock leaves the data in a consistent state, since every
+        // report is sent before the cached state gets updated.
+        let guard = self
+            .inner
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        SharedRefMut {
+            guard,
+            _ticket_release: TicketRelease {
+                tickets: &self.tickets,
+                tickets_condvar: &self.tickets_condvar,
+            },
+        }
+    }
+}
+
+#[cfg(feature = "sync")]
+#[derive(Debug, Default)]
+pub(crate) struct Tickets {
+    next_ticket: u64,
+    serving_ticket: u64,
+}
+
+// Fields are dropped in declaration order, so the ticket is released after the guard.
+#[cfg(feature = "sync")]
+pub struct SharedRefMut<'a, T> {
+    guard: std::sync::MutexGuard<'a, T>,
+    _ticket_release: TicketRelease<'a>,
+}
+
+#[cfg(feature = "sync")]
+impl<T> std::ops::Deref for SharedRefMut<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<T> std::ops::DerefMut for SharedRefMut<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+#[cfg(feature = "sync")]
+struct TicketRelease<'a> {
+    tickets: &'a std::sync::Mutex<Tickets>,
+    tickets_condvar: &'a std::sync::Condvar,
+}
+
+#[cfg(feature = "sync")]
+impl Drop for TicketRelease<'_> {
+    fn drop(&mut self) {
+        let mut tickets = self
+            .tickets
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        tickets.serving_ticket = tickets.serving_ticket.wrapping_add(1);
+
+        self.tickets_condvar.notify_all();
+    }
+}
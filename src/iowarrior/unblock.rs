@@ -0,0 +1,206 @@
+use std::collections::VecDeque;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+use std::{fmt, thread};
+
+const WORKER_IDLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+type Job = Box<dyn FnOnce() + Send>;
+
+struct WorkerPool {
+    jobs: VecDeque<Job>,
+    idle_workers: usize,
+}
+
+static WORKER_POOL: Mutex<WorkerPool> = Mutex::new(WorkerPool {
+    jobs: VecDeque::new(),
+    idle_workers: 0,
+});
+
+static WORKER_CONDVAR: Condvar = Condvar::new();
+
+#[derive(Debug)]
+struct UnblockState<T> {
+    result: Option<thread::Result<T>>,
+    waker: Option<Waker>,
+}
+
+#[derive(Debug)]
+pub struct Unblock<T> {
+    state: Arc<Mutex<UnblockState<T>>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl<T: fmt::Debug> fmt::Display for Unblock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl<T> Future for Unblock<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        match state.result.take() {
+            Some(Ok(result)) => Poll::Ready(result),
+            Some(Err(panic)) => panic::resume_unwind(panic),
+            None => {
+                state.waker = Some(cx.waker().clone());
+
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> Drop for Unblock<T> {
+    #[inline]
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl fmt::Display for CancellationToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl CancellationToken {
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+pub fn unblock<T, F>(function: F) -> Unblock<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&CancellationToken) -> T + Send + 'static,
+{
+    let state = Arc::new(Mutex::new(UnblockState {
+        result: None,
+        waker: None,
+    }));
+
+    let cancelled = Arc::new(AtomicBool::new(false));
+
+    let thread_state = state.clone();
+    let cancellation_token = CancellationToken {
+        cancelled: cancelled.clone(),
+    };
+
+    spawn_job(Box::new(move || {
+        // A panic is handed over to the awaiting task, the worker stays alive.
+        let result = panic::catch_unwind(AssertUnwindSafe(|| function(&cancellation_token)));
+
+        let mut state = thread_state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        state.result = Some(result);
+
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }));
+
+    Unblock { state, cancelled }
+}
+
+fn lock_worker_pool() -> MutexGuard<'static, WorkerPool> {
+    WORKER_POOL.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn spawn_job(job: Job) {
+    let mut worker_pool = lock_worker_pool();
+
+    worker_pool.jobs.push_back(job);
+
+    // Jobs may block for a long time, so a new worker is started when no idle one is left.
+    match worker_pool.jobs.len() > worker_pool.idle_workers {
+        true => {
+            thread::spawn(run_worker);
+        }
+        false => WORKER_CONDVAR.notify_one(),
+    }
+}
+
+fn run_worker() {
+    let mut worker_pool = lock_worker_pool();
+
+    loop {
+        if let Some(job) = worker_pool.jobs.pop_front() {
+            drop(worker_pool);
+
+            job();
+
+            worker_pool = lock_worker_pool();
+            continue;
+        }
+
+        worker_pool.idle_workers += 1;
+
+        let (guard, wait_result) = WORKER_CONDVAR
+            .wait_timeout(worker_pool, WORKER_IDLE_TIMEOUT)
+            .unwrap_or_else(PoisonError::into_inner);
+
+        worker_pool = guard;
+        worker_pool.idle_workers -= 1;
+
+        if wait_result.timed_out() && worker_pool.jobs.is_empty() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::task::Wake;
+
+    struct ThreadWaker(thread::Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut context = Context::from_waker(&waker);
+
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+
+            thread::park();
+        }
+    }
+
+    #[test]
+    fn returns_result_of_function() {
+        assert_eq!(block_on(unblock(|_| 42)), 42);
+    }
+
+    #[test]
+    fn propagates_panic() {
+        let result = panic::catch_unwind(|| block_on(unblock(|_| panic!("unblock panic"))));
+
+        assert!(result.is_err());
+        assert_eq!(block_on(unblock(|_| 7)), 7);
+    }
+}
@@ -0,0 +1,12 @@
+use crate::iowarrior::ReportId;
+use hidapi::HidError;
+use thiserror::Error;
+
+#[non_exhaustive]
+#[derive(Error, Debug)]
+pub enum IRError {
+    #[error("USB HID error.")]
+    ErrorUSB(HidError),
+    #[error("IR expected report {expected}, got {got:#04x}.")]
+    ProtocolError { expected: ReportId, got: u8 },
+}
@@ -0,0 +1,14 @@
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct IRFrame {
+    pub address: u8,
+    pub command: u8,
+    pub toggle: bool,
+}
+
+impl fmt::Display for IRFrame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
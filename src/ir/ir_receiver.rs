@@ -0,0 +1,45 @@
+use crate::iowarrior::{peripheral_service, IOWarriorData, IOWarriorMutData, Peripheral};
+use crate::iowarrior::{Shared, SharedCell};
+use crate::ir::{ir_service, IRError, IRFrame};
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Debug)]
+pub struct IRReceiver {
+    pub(crate) data: Shared<IOWarriorData>,
+    pub(crate) mut_data_refcell: Shared<SharedCell<IOWarriorMutData>>,
+}
+
+impl Drop for IRReceiver {
+    #[inline]
+    fn drop(&mut self) {
+        peripheral_service::disable_peripheral(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            Peripheral::IR,
+        );
+    }
+}
+
+impl fmt::Display for IRReceiver {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl IRReceiver {
+    #[inline]
+    pub fn read(&mut self) -> Result<IRFrame, IRError> {
+        ir_service::read_frame(&self.data, &self.mut_data_refcell)
+    }
+
+    #[inline]
+    pub fn try_read(&mut self) -> Result<Option<IRFrame>, IRError> {
+        ir_service::read_frame_timeout(&self.data, &self.mut_data_refcell, Duration::ZERO)
+    }
+
+    #[inline]
+    pub fn read_timeout(&mut self, timeout: Duration) -> Result<Option<IRFrame>, IRError> {
+        ir_service::read_frame_timeout(&self.data, &self.mut_data_refcell, timeout)
+    }
+}
@@ -0,0 +1,124 @@
+use crate::communication::communication_service;
+use crate::iowarrior::{
+    peripheral_service, IOWarriorData, IOWarriorMutData, IOWarriorType, Peripheral,
+    PeripheralSetupError, Pipe, Report, ReportId, Shared, SharedCell, SharedRefMut,
+};
+use crate::ir::{IRError, IRFrame, IRReceiver};
+use crate::pin;
+use hidapi::HidError;
+use std::time::{Duration, Instant};
+
+const WAIT_SLICE: Duration = Duration::from_millis(10);
+
+pub fn new(
+    data: &Shared<IOWarriorData>,
+    mut_data_refcell: &Shared<SharedCell<IOWarriorMutData>>,
+) -> Result<IRReceiver, PeripheralSetupError> {
+    match get_ir_pins(data.device_type) {
+        None => Err(PeripheralSetupError::NotSupported),
+        Some(ir_pins) => {
+            let mut mut_data = mut_data_refcell.borrow_mut();
+
+            peripheral_service::precheck_peripheral(data, &mut mut_data, Peripheral::IR, &ir_pins)?;
+
+            send_enable_ir(data, &mut mut_data).map_err(PeripheralSetupError::ErrorUSB)?;
+
+            peripheral_service::post_enable(&mut mut_data, &ir_pins, Peripheral::IR);
+
+            Ok(IRReceiver {
+                data: data.clone(),
+                mut_data_refcell: mut_data_refcell.clone(),
+            })
+        }
+    }
+}
+
+fn get_ir_pins(device_type: IOWarriorType) -> Option<Vec<u8>> {
+    match device_type {
+        IOWarriorType::IOWarrior24 | IOWarriorType::IOWarrior56 => Some(vec![pin!(0, 0)]),
+        IOWarriorType::IOWarrior40
+        | IOWarriorType::IOWarrior24PowerVampire
+        | IOWarriorType::IOWarrior28
+        | IOWarriorType::IOWarrior28Dongle
+        | IOWarriorType::IOWarrior28L
+        | IOWarriorType::IOWarrior56Dongle
+        | IOWarriorType::IOWarrior100 => None,
+    }
+}
+
+fn send_enable_ir(
+    data: &IOWarriorData,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
+) -> Result<(), HidError> {
+    let mut report = data.create_report(Pipe::SpecialMode);
+
+    report.buffer[0] = ReportId::IrSetup.get_value();
+    report.buffer[1] = 0x01;
+
+    communication_service::write_report(&mut mut_data.communication_data, &report)
+}
+
+pub fn read_frame(
+    data: &IOWarriorData,
+    mut_data_refcell: &Shared<SharedCell<IOWarriorMutData>>,
+) -> Result<IRFrame, IRError> {
+    loop {
+        if let Some(ir_frame) = read_frame_slice(data, mut_data_refcell, WAIT_SLICE)? {
+            return Ok(ir_frame);
+        }
+    }
+}
+
+pub fn read_frame_timeout(
+    data: &IOWarriorData,
+    mut_data_refcell: &Shared<SharedCell<IOWarriorMutData>>,
+    timeout: Duration,
+) -> Result<Option<IRFrame>, IRError> {
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+
+        let ir_frame =
+            read_frame_slice(data, mut_data_refcell, Duration::min(remaining, WAIT_SLICE))?;
+
+        if ir_frame.is_some() || remaining <= WAIT_SLICE {
+            return Ok(ir_frame);
+        }
+    }
+}
+
+// The lock is handed over after every slice, other handles wait for at most one slice.
+fn read_frame_slice(
+    data: &IOWarriorData,
+    mut_data_refcell: &Shared<SharedCell<IOWarriorMutData>>,
+    wait_duration: Duration,
+) -> Result<Option<IRFrame>, IRError> {
+    let report = communication_service::read_report_by_id_timeout(
+        &mut mut_data_refcell.borrow_mut().communication_data,
+        data.create_report(Pipe::SpecialMode),
+        &[ReportId::IrRead],
+        wait_duration,
+    )
+    .map_err(IRError::ErrorUSB)?;
+
+    match report {
+        None => Ok(None),
+        Some(report) => get_ir_frame(&report).map(Some),
+    }
+}
+
+fn get_ir_frame(report: &Report) -> Result<IRFrame, IRError> {
+    if report.buffer[0] != ReportId::IrRead.get_value() {
+        return Err(IRError::ProtocolError {
+            expected: ReportId::IrRead,
+            got: report.buffer[0],
+        });
+    }
+
+    Ok(IRFrame {
+        address: report.buffer[1] & 0x1F,
+        command: report.buffer[2] & 0x3F,
+        toggle: report.buffer[1] & 0x20 != 0,
+    })
+}
@@ -0,0 +1,8 @@
+mod ir_error;
+mod ir_frame;
+mod ir_receiver;
+pub(crate) mod ir_service;
+
+pub use self::ir_error::*;
+pub use self::ir_frame::*;
+pub use self::ir_receiver::*;
@@ -8,6 +8,7 @@ pub mod delay;
 pub mod digital;
 pub mod i2c;
 pub mod iowarrior;
+pub mod ir;
 pub mod pwm;
 pub mod spi;
 pub use communication::initialization_service::*;
@@ -1,30 +1,25 @@
 use crate::iowarrior::Peripheral;
-use crate::iowarrior::{peripheral_service, IOWarriorData, IOWarriorMutData};
+use crate::iowarrior::{peripheral_service, IOWarriorData, IOWarriorMutData, Shared, SharedCell};
 use crate::pwm::{pwm_service, PWMChannel, PWMConfig, PWMData, PWMError};
-use std::cell::RefCell;
 use std::fmt;
-use std::rc::Rc;
 
 #[derive(Debug)]
 pub struct PWM {
-    pub(crate) data: Rc<IOWarriorData>,
-    pub(crate) mut_data_refcell: Rc<RefCell<IOWarriorMutData>>,
-    pub(crate) pwm_data_refcell: Rc<RefCell<PWMData>>,
+    pub(crate) data: Shared<IOWarriorData>,
+    pub(crate) mut_data_refcell: Shared<SharedCell<IOWarriorMutData>>,
+    pub(crate) pwm_data_refcell: Shared<SharedCell<PWMData>>,
     pub(crate) channel: PWMChannel,
 }
 
 impl Drop for PWM {
     fn drop(&mut self) {
+        let mut mut_data = self.mut_data_refcell.borrow_mut();
         let mut pwm_data = self.pwm_data_refcell.borrow_mut();
 
         pwm_data.pins_counter -= 1;
 
         if pwm_data.pins_counter == 0 {
-            peripheral_service::disable_peripheral(
-                &self.data,
-                &mut self.mut_data_refcell.borrow_mut(),
-                Peripheral::PWM,
-            );
+            peripheral_service::disable_peripheral(&self.data, &mut mut_data, Peripheral::PWM);
         }
     }
 }
@@ -42,7 +37,7 @@ impl embedded_hal::pwm::ErrorType for PWM {
 impl embedded_hal::pwm::SetDutyCycle for PWM {
     #[inline]
     fn max_duty_cycle(&self) -> u16 {
-        self.pwm_data_refcell.borrow().max_duty_cycle
+        self.pwm_data_refcell.borrow_mut().max_duty_cycle
     }
 
     #[inline]
@@ -68,12 +63,14 @@ impl embedded_hal_0::PwmPin for PWM {
 
     #[inline]
     fn get_duty(&self) -> Self::Duty {
-        self.pwm_data_refcell.borrow().get_duty_cycle(self.channel)
+        self.pwm_data_refcell
+            .borrow_mut()
+            .get_duty_cycle(self.channel)
     }
 
     #[inline]
     fn get_max_duty(&self) -> Self::Duty {
-        self.pwm_data_refcell.borrow().max_duty_cycle
+        self.pwm_data_refcell.borrow_mut().max_duty_cycle
     }
 
     #[inline]
@@ -90,12 +87,12 @@ impl embedded_hal_0::PwmPin for PWM {
 impl PWM {
     #[inline]
     pub fn get_config(&self) -> PWMConfig {
-        self.pwm_data_refcell.borrow().pwm_config.clone()
+        self.pwm_data_refcell.borrow_mut().pwm_config.clone()
     }
 
     #[inline]
     pub fn get_frequency_hz(&self) -> u32 {
-        self.pwm_data_refcell.borrow().calculated_frequency_hz
+        self.pwm_data_refcell.borrow_mut().calculated_frequency_hz
     }
 
     #[inline]
@@ -105,11 +102,13 @@ impl PWM {
 
     #[inline]
     fn get_duty_cycle(&self) -> u16 {
-        self.pwm_data_refcell.borrow().get_duty_cycle(self.channel)
+        self.pwm_data_refcell
+            .borrow_mut()
+            .get_duty_cycle(self.channel)
     }
 
     #[inline]
     fn get_max_duty_cycle(&self) -> u16 {
-        self.pwm_data_refcell.borrow().max_duty_cycle
+        self.pwm_data_refcell.borrow_mut().max_duty_cycle
     }
 }
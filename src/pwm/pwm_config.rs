@@ -83,13 +83,13 @@ impl PWMChannel {
         *self as u8
     }
 
-    pub fn from_u8(channel: u8) -> PWMChannel {
+    pub fn from_u8(channel: u8) -> Option<PWMChannel> {
         match channel {
-            1 => PWMChannel::First,
-            2 => PWMChannel::Second,
-            3 => PWMChannel::Third,
-            4 => PWMChannel::Fourth,
-            _ => panic!("channel {} is not existing", channel),
+            1 => Some(PWMChannel::First),
+            2 => Some(PWMChannel::Second),
+            3 => Some(PWMChannel::Third),
+            4 => Some(PWMChannel::Fourth),
+            _ => None,
         }
     }
 }
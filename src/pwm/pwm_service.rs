@@ -1,17 +1,15 @@
 use crate::communication::communication_service;
 use crate::iowarrior::{
     peripheral_service, IOWarriorData, IOWarriorMutData, Peripheral, PeripheralSetupError, Pipe,
-    ReportId,
+    ReportId, Shared, SharedCell, SharedRefMut,
 };
 use crate::pwm::{IOW56PWMConfig, IOWarriorPWMType, PWMChannel, PWMConfig, PWMData, PWMError, PWM};
 use crate::{iowarrior::IOWarriorType, pin};
 use hidapi::HidError;
-use std::cell::{RefCell, RefMut};
-use std::rc::Rc;
 
 pub fn new(
-    data: &Rc<IOWarriorData>,
-    mut_data_refcell: &Rc<RefCell<IOWarriorMutData>>,
+    data: &Shared<IOWarriorData>,
+    mut_data_refcell: &Shared<SharedCell<IOWarriorMutData>>,
     pwm_config: PWMConfig,
 ) -> Result<Vec<PWM>, PeripheralSetupError> {
     match get_pwm_type(&data, pwm_config) {
@@ -41,21 +39,22 @@ pub fn new(
 
             peripheral_service::post_enable(&mut mut_data, &pwm_pins, Peripheral::PWM);
 
-            let pwm_data_refcell = Rc::new(RefCell::new(pwm_data));
+            let pwm_data_refcell = Shared::new(SharedCell::new(pwm_data));
 
             Ok((0..pwm_pins.len())
-                .map(|index| PWM {
+                .filter_map(|index| PWMChannel::from_u8((index + 1) as u8))
+                .map(|channel| PWM {
                     data: data.clone(),
                     mut_data_refcell: mut_data_refcell.clone(),
                     pwm_data_refcell: pwm_data_refcell.clone(),
-                    channel: PWMChannel::from_u8((index + 1) as u8),
+                    channel,
                 })
                 .collect())
         }
     }
 }
 
-fn get_pwm_type(data: &Rc<IOWarriorData>, pwm_config: PWMConfig) -> Option<IOWarriorPWMType> {
+fn get_pwm_type(data: &Shared<IOWarriorData>, pwm_config: PWMConfig) -> Option<IOWarriorPWMType> {
     if data.device_type == IOWarriorType::IOWarrior100 {
         return Some(IOWarriorPWMType::IOWarrior100);
     }
@@ -173,7 +172,7 @@ fn calculate_iow100_data(pwm_data: &mut PWMData) {
 
 fn send_enable_pwm(
     data: &IOWarriorData,
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
     pwm_data: &PWMData,
 ) -> Result<(), HidError> {
     {
@@ -239,7 +238,7 @@ fn write_u16(bytes: &mut [u8], value: u16) {
 #[inline]
 pub fn update_duty_cycle(
     data: &IOWarriorData,
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
     pwm_data: &PWMData,
 ) -> Result<(), PWMError> {
     send_enable_pwm(data, mut_data, pwm_data).map_err(|x| PWMError::ErrorUSB(x))
@@ -1,4 +1,6 @@
 mod spi;
+#[cfg(feature = "embedded-hal-async")]
+mod spi_async;
 mod spi_config;
 mod spi_data;
 mod spi_error;
@@ -1,15 +1,12 @@
 use crate::iowarrior::Peripheral;
-use crate::iowarrior::{peripheral_service, IOWarriorData, IOWarriorMutData};
+use crate::iowarrior::{peripheral_service, IOWarriorData, IOWarriorMutData, Shared, SharedCell};
 use crate::spi::{spi_service, SPIConfig, SPIData, SPIError};
-use std::cell::RefCell;
 use std::fmt;
-use std::rc::Rc;
-use std::time::Duration;
 
 #[derive(Debug)]
 pub struct SPI {
-    pub(crate) data: Rc<IOWarriorData>,
-    pub(crate) mut_data_refcell: Rc<RefCell<IOWarriorMutData>>,
+    pub(crate) data: Shared<IOWarriorData>,
+    pub(crate) mut_data_refcell: Shared<SharedCell<IOWarriorMutData>>,
     pub(crate) spi_data: SPIData,
 }
 
@@ -173,40 +170,12 @@ impl embedded_hal::spi::SpiDevice for SPI {
         &mut self,
         operations: &mut [embedded_hal::spi::Operation<'_, u8>],
     ) -> Result<(), Self::Error> {
-        let mut mut_data = self.mut_data_refcell.borrow_mut();
-
-        for operation in operations {
-            match operation {
-                embedded_hal::spi::Operation::Read(read) => {
-                    spi_service::read_data(&self.data, &mut mut_data, &self.spi_data, read)?;
-                }
-                embedded_hal::spi::Operation::Write(write) => {
-                    spi_service::write_data(&self.data, &mut mut_data, &self.spi_data, write)?;
-                }
-                embedded_hal::spi::Operation::Transfer(read, write) => {
-                    spi_service::transfer_data_with_different_size(
-                        &self.data,
-                        &mut mut_data,
-                        &self.spi_data,
-                        read,
-                        write,
-                    )?;
-                }
-                embedded_hal::spi::Operation::TransferInPlace(buf) => {
-                    spi_service::transfer_data_in_place(
-                        &self.data,
-                        &mut mut_data,
-                        &self.spi_data,
-                        buf,
-                    )?;
-                }
-                embedded_hal::spi::Operation::DelayNs(delay_ns) => {
-                    std::thread::sleep(Duration::from_nanos(delay_ns.clone() as u64));
-                }
-            }
-        }
-
-        Ok(())
+        spi_service::transaction(
+            &self.data,
+            &mut self.mut_data_refcell.borrow_mut(),
+            &self.spi_data,
+            operations,
+        )
     }
 
     #[inline]
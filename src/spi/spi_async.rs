@@ -0,0 +1,124 @@
+use crate::iowarrior::unblock;
+use crate::spi::{spi_service, SPIError, SPI};
+use embedded_hal_async::spi::Operation;
+use std::fmt;
+
+#[derive(Debug)]
+enum OwnedOperation {
+    Read(Vec<u8>),
+    Write(Vec<u8>),
+    Transfer(Vec<u8>, Vec<u8>),
+    TransferInPlace(Vec<u8>),
+    DelayNs(u32),
+}
+
+impl fmt::Display for OwnedOperation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl embedded_hal_async::spi::SpiBus<u8> for SPI {
+    #[inline]
+    async fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        self.transaction_async(&mut [Operation::Read(words)]).await
+    }
+
+    #[inline]
+    async fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        self.transaction_async(&mut [Operation::Write(words)]).await
+    }
+
+    #[inline]
+    async fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+        self.transaction_async(&mut [Operation::Transfer(read, write)])
+            .await
+    }
+
+    #[inline]
+    async fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        self.transaction_async(&mut [Operation::TransferInPlace(words)])
+            .await
+    }
+
+    #[inline]
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl embedded_hal_async::spi::SpiDevice for SPI {
+    #[inline]
+    async fn transaction(
+        &mut self,
+        operations: &mut [Operation<'_, u8>],
+    ) -> Result<(), Self::Error> {
+        self.transaction_async(operations).await
+    }
+}
+
+impl SPI {
+    async fn transaction_async(
+        &mut self,
+        operations: &mut [Operation<'_, u8>],
+    ) -> Result<(), SPIError> {
+        let data = self.data.clone();
+        let mut_data_refcell = self.mut_data_refcell.clone();
+        let spi_data = self.spi_data;
+
+        let mut owned_operations: Vec<OwnedOperation> = operations
+            .iter()
+            .map(|operation| match operation {
+                Operation::Read(read) => OwnedOperation::Read(vec![0u8; read.len()]),
+                Operation::Write(write) => OwnedOperation::Write(write.to_vec()),
+                Operation::Transfer(read, write) => {
+                    OwnedOperation::Transfer(vec![0u8; read.len()], write.to_vec())
+                }
+                Operation::TransferInPlace(buffer) => {
+                    OwnedOperation::TransferInPlace(buffer.to_vec())
+                }
+                Operation::DelayNs(delay_ns) => OwnedOperation::DelayNs(*delay_ns),
+            })
+            .collect();
+
+        let owned_operations = unblock(move |_| {
+            {
+                let mut borrowed_operations: Vec<Operation<'_, u8>> = owned_operations
+                    .iter_mut()
+                    .map(|operation| match operation {
+                        OwnedOperation::Read(read) => Operation::Read(read),
+                        OwnedOperation::Write(write) => Operation::Write(write),
+                        OwnedOperation::Transfer(read, write) => Operation::Transfer(read, write),
+                        OwnedOperation::TransferInPlace(buffer) => {
+                            Operation::TransferInPlace(buffer)
+                        }
+                        OwnedOperation::DelayNs(delay_ns) => Operation::DelayNs(*delay_ns),
+                    })
+                    .collect();
+
+                spi_service::transaction(
+                    &data,
+                    &mut mut_data_refcell.borrow_mut(),
+                    &spi_data,
+                    &mut borrowed_operations,
+                )?;
+            }
+
+            Ok::<Vec<OwnedOperation>, SPIError>(owned_operations)
+        })
+        .await?;
+
+        for (operation, owned_operation) in operations.iter_mut().zip(owned_operations) {
+            match (operation, owned_operation) {
+                (Operation::Read(buffer), OwnedOperation::Read(read))
+                | (Operation::Transfer(buffer, _), OwnedOperation::Transfer(read, _))
+                | (Operation::TransferInPlace(buffer), OwnedOperation::TransferInPlace(read)) => {
+                    buffer.copy_from_slice(&read);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use crate::iowarrior::ReportId;
 use hidapi::HidError;
 use thiserror::Error;
 
@@ -8,12 +9,16 @@ pub enum SPIError {
     ErrorUSB(HidError),
     #[error("SPI input output error.")]
     IOErrorSPI,
+    #[error("SPI expected report {expected}, got {got:#04x}.")]
+    ProtocolError { expected: ReportId, got: u8 },
 }
 
 impl embedded_hal::spi::Error for SPIError {
     fn kind(&self) -> embedded_hal::spi::ErrorKind {
         match self {
-            SPIError::ErrorUSB(_) | SPIError::IOErrorSPI => embedded_hal::spi::ErrorKind::Other,
+            SPIError::ErrorUSB(_) | SPIError::IOErrorSPI | SPIError::ProtocolError { .. } => {
+                embedded_hal::spi::ErrorKind::Other
+            }
         }
     }
 }
@@ -3,20 +3,19 @@ use crate::bits::Bitmasking;
 use crate::communication::communication_service;
 use crate::iowarrior::{
     peripheral_service, IOWarriorData, IOWarriorMutData, Peripheral, PeripheralSetupError, Pipe,
-    Report, ReportId,
+    Report, ReportId, Shared, SharedCell, SharedRefMut,
 };
 use crate::spi::spi_data::{IOWarriorSPIType, SPIData};
 use crate::spi::{SPIConfig, SPIError, SPIMode, SPI};
 use crate::{iowarrior::IOWarriorType, pin};
 use hidapi::HidError;
-use std::cell::{RefCell, RefMut};
 use std::cmp::Ordering;
 use std::iter;
-use std::rc::Rc;
+use std::time::Duration;
 
 pub fn new(
-    data: &Rc<IOWarriorData>,
-    mut_data_refcell: &Rc<RefCell<IOWarriorMutData>>,
+    data: &Shared<IOWarriorData>,
+    mut_data_refcell: &Shared<SharedCell<IOWarriorMutData>>,
     spi_config: SPIConfig,
 ) -> Result<SPI, PeripheralSetupError> {
     match get_spi_type(&data) {
@@ -54,7 +53,7 @@ pub fn new(
     }
 }
 
-fn get_spi_type(data: &Rc<IOWarriorData>) -> Option<IOWarriorSPIType> {
+fn get_spi_type(data: &Shared<IOWarriorData>) -> Option<IOWarriorSPIType> {
     match data.device_type {
         IOWarriorType::IOWarrior24 | IOWarriorType::IOWarrior24PowerVampire => {
             Some(IOWarriorSPIType::IOWarrior24)
@@ -134,7 +133,7 @@ fn calculate_iow56_data(spi_data: &mut SPIData) {
 
 fn send_enable_spi(
     data: &IOWarriorData,
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
     spi_data: &SPIData,
 ) -> Result<(), HidError> {
     let mut report = data.create_report(Pipe::SpecialMode);
@@ -200,8 +199,8 @@ fn send_enable_spi(
 }
 
 pub fn read_data(
-    data: &Rc<IOWarriorData>,
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    data: &Shared<IOWarriorData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
     spi_data: &SPIData,
     words: &mut [u8],
 ) -> Result<(), SPIError> {
@@ -232,8 +231,8 @@ pub fn read_data(
 }
 
 pub fn write_data(
-    data: &Rc<IOWarriorData>,
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    data: &Shared<IOWarriorData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
     spi_data: &SPIData,
     words: &[u8],
 ) -> Result<(), SPIError> {
@@ -269,8 +268,8 @@ pub fn write_data(
 }
 
 pub fn transfer_data_with_different_size(
-    data: &Rc<IOWarriorData>,
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    data: &Shared<IOWarriorData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
     spi_data: &SPIData,
     read: &mut [u8],
     write: &[u8],
@@ -310,8 +309,8 @@ pub fn transfer_data_with_different_size(
 }
 
 pub fn transfer_data_with_same_size(
-    data: &Rc<IOWarriorData>,
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    data: &Shared<IOWarriorData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
     spi_data: &SPIData,
     read: &mut [u8],
     write: &[u8],
@@ -342,8 +341,8 @@ pub fn transfer_data_with_same_size(
 }
 
 pub fn transfer_data_in_place(
-    data: &Rc<IOWarriorData>,
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    data: &Shared<IOWarriorData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
     spi_data: &SPIData,
     words: &mut [u8],
 ) -> Result<(), SPIError> {
@@ -371,7 +370,36 @@ pub fn transfer_data_in_place(
     Ok(())
 }
 
-fn get_chunk_size(data: &Rc<IOWarriorData>, spi_data: &SPIData) -> usize {
+pub fn transaction(
+    data: &Shared<IOWarriorData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
+    spi_data: &SPIData,
+    operations: &mut [embedded_hal::spi::Operation<'_, u8>],
+) -> Result<(), SPIError> {
+    for operation in operations {
+        match operation {
+            embedded_hal::spi::Operation::Read(read) => {
+                read_data(data, mut_data, spi_data, read)?;
+            }
+            embedded_hal::spi::Operation::Write(write) => {
+                write_data(data, mut_data, spi_data, write)?;
+            }
+            embedded_hal::spi::Operation::Transfer(read, write) => {
+                transfer_data_with_different_size(data, mut_data, spi_data, read, write)?;
+            }
+            embedded_hal::spi::Operation::TransferInPlace(buf) => {
+                transfer_data_in_place(data, mut_data, spi_data, buf)?;
+            }
+            embedded_hal::spi::Operation::DelayNs(delay_ns) => {
+                std::thread::sleep(Duration::from_nanos(*delay_ns as u64));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn get_chunk_size(data: &Shared<IOWarriorData>, spi_data: &SPIData) -> usize {
     data.special_report_size
         - match spi_data.spi_type {
             IOWarriorSPIType::IOWarrior24 => 2usize,
@@ -380,8 +408,8 @@ fn get_chunk_size(data: &Rc<IOWarriorData>, spi_data: &SPIData) -> usize {
 }
 
 fn write_report(
-    data: &Rc<IOWarriorData>,
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    data: &Shared<IOWarriorData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
     spi_data: &SPIData,
     write_chunk: &[u8],
     use_data_ready_pin: bool,
@@ -429,22 +457,28 @@ fn write_report(
 }
 
 fn read_report(
-    data: &Rc<IOWarriorData>,
-    mut_data: &mut RefMut<IOWarriorMutData>,
+    data: &Shared<IOWarriorData>,
+    mut_data: &mut SharedRefMut<IOWarriorMutData>,
     spi_data: &SPIData,
     read_chunk: &mut [u8],
 ) -> Result<(), SPIError> {
-    let report = communication_service::read_report(
+    let report = communication_service::read_report_by_id(
         &mut mut_data.communication_data,
         data.create_report(Pipe::SpecialMode),
+        &[ReportId::SpiTransfer],
     )
     .map_err(|x| SPIError::ErrorUSB(x))?;
 
-    assert_eq!(report.buffer[0], ReportId::SpiTransfer.get_value());
+    if report.buffer[0] != ReportId::SpiTransfer.get_value() {
+        return Err(SPIError::ProtocolError {
+            expected: ReportId::SpiTransfer,
+            got: report.buffer[0],
+        });
+    }
 
     match read_chunk.len() as u8 == report.buffer[1] {
         true => {
-            read_chunk.copy_from_slice(&report.buffer[1..(read_chunk.len() + 1)]);
+            read_chunk.copy_from_slice(&report.buffer[2..(read_chunk.len() + 2)]);
             Ok(())
         }
         false => Err(SPIError::IOErrorSPI),
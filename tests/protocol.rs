@@ -0,0 +1,261 @@
+use embedded_hal::digital::InputPin;
+use embedded_hal::i2c::I2c;
+use embedded_hal::spi::SpiBus;
+use hidapi::HidError;
+use iowarrior_embedded_hal::adc::{ADCChannel, ADCSample};
+use iowarrior_embedded_hal::bits::Bit;
+use iowarrior_embedded_hal::communication::Transport;
+use iowarrior_embedded_hal::i2c::I2CConfig;
+use iowarrior_embedded_hal::iowarrior::{
+    create_iowarrior, IOWarrior, IOWarriorType, Report, ReportId,
+};
+use iowarrior_embedded_hal::pin;
+use iowarrior_embedded_hal::pwm::PWMChannel;
+use std::time::Duration;
+
+const DEVICE_TYPES: [IOWarriorType; 9] = [
+    IOWarriorType::IOWarrior40,
+    IOWarriorType::IOWarrior24,
+    IOWarriorType::IOWarrior24PowerVampire,
+    IOWarriorType::IOWarrior56,
+    IOWarriorType::IOWarrior56Dongle,
+    IOWarriorType::IOWarrior28,
+    IOWarriorType::IOWarrior28Dongle,
+    IOWarriorType::IOWarrior28L,
+    IOWarriorType::IOWarrior100,
+];
+
+const REPORT_IDS: [ReportId; 16] = [
+    ReportId::AdcSetup,
+    ReportId::AdcRead,
+    ReportId::I2cSetup,
+    ReportId::I2cWrite,
+    ReportId::I2cRead,
+    ReportId::IrSetup,
+    ReportId::IrRead,
+    ReportId::PwmSetup,
+    ReportId::PwmParameters,
+    ReportId::SpiSetup,
+    ReportId::SpiTransfer,
+    ReportId::TimerSetup,
+    ReportId::TimerDataA,
+    ReportId::TimerDataB,
+    ReportId::GpioReadWrite,
+    ReportId::GpioSpecialRead,
+];
+
+const SEEDS: u64 = 200;
+
+// Answers every read with random bytes. Most reports start with the id of the last request or
+// another valid id and a small status byte, so parsing gets past the header checks. Non blocking
+// reads come back empty half the time to end drain loops.
+#[derive(Debug)]
+struct RandomTransport {
+    state: u64,
+    last_report_id: u8,
+}
+
+impl RandomTransport {
+    fn new(seed: u64) -> RandomTransport {
+        RandomTransport {
+            state: seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1,
+            last_report_id: 0,
+        }
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    fn fill(&mut self, mut report: Report) -> Report {
+        for byte in report.buffer.iter_mut() {
+            *byte = self.next() as u8;
+        }
+
+        if report.buffer.len() > 1 {
+            report.buffer[0] = match self.next() % 4 {
+                0 | 1 => self.last_report_id,
+                2 => REPORT_IDS[self.next() as usize % REPORT_IDS.len()].get_value(),
+                _ => report.buffer[0],
+            };
+
+            if self.next() & 1 == 0 {
+                report.buffer[1] %= 64;
+            }
+        }
+
+        report
+    }
+
+    fn fill_sometimes(&mut self, report: Report) -> Option<Report> {
+        match self.next() & 1 == 0 {
+            true => Some(self.fill(report)),
+            false => None,
+        }
+    }
+}
+
+impl Transport for RandomTransport {
+    fn write_report(&mut self, report: &Report) -> Result<(), HidError> {
+        if let Some(report_id) = report.buffer.first() {
+            self.last_report_id = *report_id;
+        }
+
+        Ok(())
+    }
+
+    fn read_report(&mut self, report: Report) -> Result<Report, HidError> {
+        Ok(self.fill(report))
+    }
+
+    fn read_report_non_blocking(&mut self, report: Report) -> Result<Option<Report>, HidError> {
+        Ok(self.fill_sometimes(report))
+    }
+
+    fn read_report_timeout(
+        &mut self,
+        report: Report,
+        _timeout: Duration,
+    ) -> Result<Option<Report>, HidError> {
+        Ok(self.fill_sometimes(report))
+    }
+}
+
+fn create_random_iowarrior(device_type: IOWarriorType, seed: u64) -> Option<IOWarrior> {
+    create_iowarrior(
+        device_type,
+        0x2000,
+        String::new(),
+        Box::new(RandomTransport::new(seed)),
+    )
+    .ok()
+}
+
+#[test]
+fn random_pins_reports_do_not_panic() {
+    for device_type in DEVICE_TYPES {
+        for seed in 0..SEEDS {
+            let Some(iowarrior) = create_random_iowarrior(device_type, seed) else {
+                continue;
+            };
+
+            if let Ok(mut input_pin) = iowarrior.setup_input(pin!(0, 1)) {
+                for _ in 0..8 {
+                    _ = input_pin.is_high();
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn random_i2c_reports_do_not_panic() {
+    let mut i2c_config = I2CConfig::default();
+
+    i2c_config.timeout = Some(Duration::from_millis(1));
+
+    for device_type in DEVICE_TYPES {
+        for seed in 0..SEEDS {
+            let Some(iowarrior) = create_random_iowarrior(device_type, seed) else {
+                continue;
+            };
+
+            let Ok(mut i2c) = iowarrior.setup_i2c_with_config(i2c_config) else {
+                continue;
+            };
+
+            let mut buffer = [0u8; 20];
+
+            _ = i2c.write(0x50u8, &[0x10, 0x20, 0x30]);
+            _ = i2c.read(0x50u8, &mut buffer);
+            _ = i2c.write_read(0x50u8, &[0x10], &mut buffer[..4]);
+        }
+    }
+}
+
+#[test]
+fn random_spi_reports_do_not_panic() {
+    for device_type in DEVICE_TYPES {
+        for seed in 0..SEEDS {
+            let Some(iowarrior) = create_random_iowarrior(device_type, seed) else {
+                continue;
+            };
+
+            let Ok(mut spi) = iowarrior.setup_spi() else {
+                continue;
+            };
+
+            let write: Vec<u8> = (0..80).collect();
+            let mut read = [0u8; 80];
+
+            _ = spi.transfer(&mut read, &write);
+            _ = spi.read(&mut read[..7]);
+        }
+    }
+}
+
+#[test]
+fn random_adc_reports_do_not_panic() {
+    for device_type in DEVICE_TYPES {
+        for seed in 0..SEEDS {
+            let Some(iowarrior) = create_random_iowarrior(device_type, seed) else {
+                continue;
+            };
+
+            let Ok(mut adc) = iowarrior.setup_adc() else {
+                continue;
+            };
+
+            let mut buffer: [Option<ADCSample>; 64] = [None; 64];
+
+            _ = adc.read(&mut buffer);
+            _ = adc.read(&mut buffer);
+        }
+    }
+}
+
+#[test]
+fn bit_from_u8_accepts_only_bit_indices() {
+    for value in 0..=u8::MAX {
+        match Bit::from_u8(value) {
+            Some(bit) => assert_eq!(bit as u8, value),
+            None => assert!(value > 7),
+        }
+    }
+}
+
+#[test]
+fn adc_channel_from_u8_accepts_only_channels() {
+    for value in 0..=u8::MAX {
+        match ADCChannel::from_u8(value) {
+            Some(channel) => assert_eq!(channel as u8, value),
+            None => assert!(value == 0 || value > 8),
+        }
+    }
+}
+
+#[test]
+fn pwm_channel_from_u8_accepts_only_channels() {
+    for value in 0..=u8::MAX {
+        match PWMChannel::from_u8(value) {
+            Some(channel) => assert_eq!(channel as u8, value),
+            None => assert!(value == 0 || value > 4),
+        }
+    }
+}
+
+#[test]
+fn report_id_from_u8_matches_get_value() {
+    for value in 0..=u8::MAX {
+        if let Some(report_id) = ReportId::from_u8(value) {
+            assert_eq!(report_id.get_value(), value);
+        }
+    }
+
+    for report_id in REPORT_IDS {
+        assert_eq!(ReportId::from_u8(report_id.get_value()), Some(report_id));
+    }
+}
@@ -0,0 +1,809 @@
+use embedded_hal::digital::{InputPin, OutputPin, PinState, StatefulOutputPin};
+use embedded_hal::i2c::{I2c, Operation};
+use embedded_hal::spi::SpiBus;
+use iowarrior_embedded_hal::communication::simulator::{
+    get_simulated_iowarrior, get_simulator_control,
+};
+#[cfg(feature = "sync")]
+use iowarrior_embedded_hal::digital::WaveformStep;
+use iowarrior_embedded_hal::digital::{Debounce, PinError};
+use iowarrior_embedded_hal::i2c::{
+    I2CConfig, I2CDevice, I2CError, I2CRegisterWidth, IOW100Speed, IOW56Clock,
+};
+use iowarrior_embedded_hal::iowarrior::IOWarriorType;
+use iowarrior_embedded_hal::ir::IRFrame;
+use iowarrior_embedded_hal::pin;
+use std::time::{Duration, Instant};
+
+#[test]
+fn output_pin_is_read_back_by_port() {
+    let iowarrior = get_simulated_iowarrior(IOWarriorType::IOWarrior40, 0x2000).unwrap();
+
+    let mut output_pin = iowarrior.setup_output_as_high(pin!(0, 0)).unwrap();
+    let mut input_pin = iowarrior.setup_input(pin!(0, 1)).unwrap();
+
+    output_pin.set_low().unwrap();
+
+    assert!(output_pin.is_set_low().unwrap());
+    assert!(input_pin.is_high().unwrap());
+
+    drop(output_pin);
+
+    let mut gpio_port = iowarrior
+        .setup_gpio_port(&[pin!(1, 0), pin!(1, 1), pin!(1, 2)])
+        .unwrap();
+
+    gpio_port.write(0b101u8).unwrap();
+
+    assert_eq!(gpio_port.read().unwrap(), 0b101);
+}
+
+#[test]
+fn pin_is_released_on_drop() {
+    let iowarrior = get_simulated_iowarrior(IOWarriorType::IOWarrior24, 0x2000).unwrap();
+
+    let output_pin = iowarrior.setup_output_as_high(pin!(0, 3)).unwrap();
+
+    assert!(iowarrior.setup_input(pin!(0, 3)).is_err());
+
+    drop(output_pin);
+
+    assert!(iowarrior.setup_input(pin!(0, 3)).is_ok());
+}
+
+#[test]
+fn converted_pin_stays_in_use() {
+    let iowarrior = get_simulated_iowarrior(IOWarriorType::IOWarrior40, 0x2000).unwrap();
+    let control = get_simulator_control(&iowarrior.get_serial_number()).unwrap();
+
+    let output_pin = iowarrior.setup_output_as_low(pin!(0, 4)).unwrap();
+
+    let mut input_pin = output_pin.into_input().unwrap();
+
+    assert!(input_pin.is_high().unwrap());
+    assert!(iowarrior.setup_input(pin!(0, 4)).is_err());
+
+    control.set_pin_input_state(pin!(0, 4), PinState::Low);
+
+    assert!(input_pin.is_low().unwrap());
+
+    control.set_pin_input_state(pin!(0, 4), PinState::High);
+
+    let mut output_pin = input_pin.into_output(PinState::Low).unwrap();
+
+    assert!(output_pin.is_set_low().unwrap());
+    assert!(iowarrior.setup_input(pin!(0, 4)).is_err());
+
+    drop(output_pin);
+
+    assert!(iowarrior.setup_input(pin!(0, 4)).is_ok());
+}
+
+#[test]
+fn failed_conversion_returns_the_pin() {
+    let iowarrior = get_simulated_iowarrior(IOWarriorType::IOWarrior40, 0x2000).unwrap();
+    let control = get_simulator_control(&iowarrior.get_serial_number()).unwrap();
+
+    let output_pin = iowarrior.setup_output_as_low(pin!(0, 5)).unwrap();
+    let input_pin = iowarrior.setup_input(pin!(0, 6)).unwrap();
+
+    control.disconnect();
+
+    let mut output_pin = match output_pin.into_input() {
+        Ok(_) => panic!("conversion must fail while disconnected"),
+        Err((pin, error)) => {
+            assert!(matches!(error, PinError::ErrorUSB(_)));
+            pin
+        }
+    };
+
+    assert!(output_pin.is_set_low().unwrap());
+
+    match input_pin.into_output(PinState::Low) {
+        Ok(_) => panic!("conversion must fail while disconnected"),
+        Err((_, error)) => assert!(matches!(error, PinError::ErrorUSB(_))),
+    }
+
+    assert!(iowarrior.setup_input(pin!(0, 5)).is_err());
+}
+
+#[test]
+fn flex_pin_drives_low_and_releases_high() {
+    let iowarrior = get_simulated_iowarrior(IOWarriorType::IOWarrior40, 0x2000).unwrap();
+    let control = get_simulator_control(&iowarrior.get_serial_number()).unwrap();
+
+    let mut flex_pin = iowarrior.setup_flex_pin(pin!(0, 2)).unwrap();
+
+    assert!(flex_pin.is_set_high().unwrap());
+    assert!(flex_pin.is_high().unwrap());
+
+    flex_pin.set_low().unwrap();
+
+    assert!(flex_pin.is_set_low().unwrap());
+    assert!(flex_pin.is_low().unwrap());
+
+    flex_pin.set_high().unwrap();
+
+    assert!(flex_pin.is_high().unwrap());
+
+    // A released pin reads the level another device pulls it to.
+    control.set_pin_input_state(pin!(0, 2), PinState::Low);
+
+    assert!(flex_pin.is_set_high().unwrap());
+    assert!(flex_pin.is_low().unwrap());
+
+    assert!(iowarrior.setup_input(pin!(0, 2)).is_err());
+
+    drop(flex_pin);
+
+    assert!(iowarrior.setup_input(pin!(0, 2)).is_ok());
+}
+
+#[test]
+fn i2c_memory_round_trip() {
+    for device_type in [IOWarriorType::IOWarrior40, IOWarriorType::IOWarrior100] {
+        let iowarrior = get_simulated_iowarrior(device_type, 0x2000).unwrap();
+        let control = get_simulator_control(&iowarrior.get_serial_number()).unwrap();
+
+        control.attach_i2c_memory(0x50);
+
+        let mut i2c = iowarrior.setup_i2c().unwrap();
+
+        let written: Vec<u8> = (0..40).collect();
+        let mut write_buffer = vec![0x10u8];
+        write_buffer.extend_from_slice(&written);
+
+        i2c.write(0x50u8, &write_buffer).unwrap();
+
+        let mut read = [0u8; 40];
+
+        i2c.write(0x50u8, &[0x10]).unwrap();
+        i2c.read(0x50u8, &mut read).unwrap();
+
+        assert_eq!(read.as_slice(), written.as_slice());
+    }
+}
+
+#[test]
+fn i2c_write_read_stays_one_transfer() {
+    let iowarrior = get_simulated_iowarrior(IOWarriorType::IOWarrior40, 0x2000).unwrap();
+    let control = get_simulator_control(&iowarrior.get_serial_number()).unwrap();
+
+    control.attach_i2c_memory(0x50);
+
+    let mut i2c = iowarrior.setup_i2c().unwrap();
+
+    i2c.write(0x50u8, &[0x20, 0xAA, 0xBB]).unwrap();
+
+    let mut read = [0u8; 2];
+
+    i2c.write_read(0x50u8, &[0x20], &mut read).unwrap();
+
+    assert_eq!(read, [0xAA, 0xBB]);
+
+    // The IOWarrior40 reads at most 6 bytes per report.
+    let mut long_read = [0u8; 7];
+
+    assert!(matches!(
+        i2c.write_read(0x50u8, &[0x20], &mut long_read),
+        Err(I2CError::ReadAfterWriteTooLong { max_length: 6 })
+    ));
+
+    i2c.transaction(
+        0x50u8,
+        &mut [Operation::Write(&[0x20]), Operation::Read(&mut [])],
+    )
+    .unwrap();
+
+    i2c.write_read(0x50u8, &[0x21], &mut read[..1]).unwrap();
+
+    assert_eq!(read[0], 0xBB);
+
+    // Every read ends with a stop condition.
+    assert!(matches!(
+        i2c.transaction(
+            0x50u8,
+            &mut [Operation::Read(&mut read), Operation::Write(&[0x20])],
+        ),
+        Err(I2CError::RepeatedStartNotSupported)
+    ));
+}
+
+#[test]
+fn i2c_device_burst_spans_several_reports() {
+    // Burst reads are split into chunks of 6 and 62 bytes.
+    for device_type in [IOWarriorType::IOWarrior40, IOWarriorType::IOWarrior100] {
+        let iowarrior = get_simulated_iowarrior(device_type, 0x2000).unwrap();
+        let control = get_simulator_control(&iowarrior.get_serial_number()).unwrap();
+
+        control.attach_i2c_memory(0x50);
+
+        let shared_i2c = iowarrior.setup_i2c().unwrap().into_shared();
+
+        let mut i2c_device = I2CDevice::new(shared_i2c, 0x50, I2CRegisterWidth::Bits8).unwrap();
+
+        let written: Vec<u8> = (0..150).collect();
+
+        i2c_device.write_burst(0x30, &written).unwrap();
+
+        let mut read = [0u8; 150];
+
+        i2c_device.read_burst(0x30, &mut read).unwrap();
+
+        assert_eq!(read.as_slice(), written.as_slice());
+    }
+}
+
+#[test]
+fn i2c_rejects_reserved_address() {
+    let iowarrior = get_simulated_iowarrior(IOWarriorType::IOWarrior56, 0x2000).unwrap();
+
+    let mut i2c = iowarrior.setup_i2c().unwrap();
+
+    assert!(i2c.write(0x7Fu8, &[0x00]).is_err());
+}
+
+#[test]
+fn i2c_scan_finds_attached_devices() {
+    for device_type in [IOWarriorType::IOWarrior40, IOWarriorType::IOWarrior100] {
+        let iowarrior = get_simulated_iowarrior(device_type, 0x2000).unwrap();
+        let control = get_simulator_control(&iowarrior.get_serial_number()).unwrap();
+
+        control.attach_i2c_memory(0x20);
+        control.attach_i2c_memory(0x50);
+        control.attach_i2c_memory(0x77);
+
+        let mut i2c = iowarrior.setup_i2c().unwrap();
+
+        assert_eq!(i2c.scan().unwrap(), [0x20, 0x50, 0x77]);
+
+        assert!(matches!(
+            i2c.write(0x51u8, &[0x00]),
+            Err(I2CError::NoAcknowledge)
+        ));
+    }
+}
+
+#[test]
+fn i2c_set_config_keeps_the_peripheral() {
+    let mut i2c_config = I2CConfig::default();
+
+    i2c_config.iow56_clock = IOW56Clock::Slow46kHz;
+    i2c_config.iow100_speed = IOW100Speed::ExtraSlow10kb;
+    i2c_config.timeout = Some(Duration::from_millis(20));
+
+    for (device_type, clock) in [
+        (IOWarriorType::IOWarrior56, IOW56Clock::Slow46kHz as u8),
+        (
+            IOWarriorType::IOWarrior100,
+            IOW100Speed::ExtraSlow10kb as u8,
+        ),
+    ] {
+        let iowarrior = get_simulated_iowarrior(device_type, 0x2000).unwrap();
+        let control = get_simulator_control(&iowarrior.get_serial_number()).unwrap();
+
+        control.attach_i2c_memory(0x50);
+
+        let mut i2c = iowarrior.setup_i2c().unwrap();
+
+        i2c.write(0x50u8, &[0x10, 0x5A]).unwrap();
+
+        assert_eq!(control.get_i2c_clock(), Some(0));
+
+        i2c.set_config(i2c_config).unwrap();
+
+        assert_eq!(control.get_i2c_clock(), Some(clock));
+        assert!(iowarrior.setup_i2c().is_err());
+
+        let mut read = [0u8; 1];
+
+        i2c.write_read(0x50u8, &[0x10], &mut read).unwrap();
+
+        assert_eq!(read, [0x5A]);
+
+        // The new timeout applies as well.
+        control.set_i2c_stalled(true);
+
+        let start = Instant::now();
+
+        assert!(matches!(i2c.write(0x50u8, &[0x10]), Err(I2CError::Timeout)));
+        assert!(start.elapsed() < Duration::from_millis(500));
+    }
+}
+
+#[test]
+fn i2c_ten_bit_address_round_trip() {
+    for device_type in [IOWarriorType::IOWarrior40, IOWarriorType::IOWarrior100] {
+        let iowarrior = get_simulated_iowarrior(device_type, 0x2000).unwrap();
+        let control = get_simulator_control(&iowarrior.get_serial_number()).unwrap();
+
+        control.attach_ten_bit_i2c_memory(0x2A5);
+        control.attach_i2c_memory(0x52);
+
+        let i2c = iowarrior.setup_i2c().unwrap();
+        let mut shared_i2c = i2c.into_shared();
+        let mut other_i2c = shared_i2c.clone();
+
+        shared_i2c.write(0x2A5u16, &[0x10, 0x11, 0x22]).unwrap();
+        other_i2c.write(0x52u8, &[0x10, 0x33, 0x44]).unwrap();
+
+        let mut read = [0u8; 2];
+
+        shared_i2c.write_read(0x2A5u16, &[0x10], &mut read).unwrap();
+        assert_eq!(read, [0x11, 0x22]);
+
+        other_i2c.write_read(0x52u8, &[0x10], &mut read).unwrap();
+        assert_eq!(read, [0x33, 0x44]);
+
+        assert!(matches!(
+            shared_i2c.write(0x400u16, &[0x00]),
+            Err(I2CError::InvalidTenBitAddress)
+        ));
+    }
+}
+
+#[test]
+fn i2c_drops_late_responses_after_timeout() {
+    let iowarrior = get_simulated_iowarrior(IOWarriorType::IOWarrior24, 0x2000).unwrap();
+    let control = get_simulator_control(&iowarrior.get_serial_number()).unwrap();
+
+    control.attach_i2c_memory(0x50);
+
+    let mut i2c_config = I2CConfig::default();
+
+    i2c_config.timeout = Some(Duration::from_millis(20));
+
+    let mut i2c = iowarrior.setup_i2c_with_config(i2c_config).unwrap();
+    let mut ir_receiver = iowarrior.setup_ir_receiver().unwrap();
+
+    i2c.write(0x50u8, &[0x40, 0x11, 0x22]).unwrap();
+
+    control.set_i2c_stalled(true);
+
+    assert!(matches!(
+        i2c.write(0x50u8, &[0x42, 0x33]),
+        Err(I2CError::Timeout)
+    ));
+
+    let ir_frame = IRFrame {
+        address: 0x01,
+        command: 0x02,
+        toggle: false,
+    };
+
+    control.receive_ir_frame(ir_frame);
+    control.set_i2c_stalled(false);
+
+    // The late write response is dropped, the IR frame on the same interface is kept.
+    let mut read = [0u8; 3];
+
+    i2c.write_read(0x50u8, &[0x40], &mut read).unwrap();
+
+    assert_eq!(read, [0x11, 0x22, 0x33]);
+    assert_eq!(ir_receiver.try_read().unwrap(), Some(ir_frame));
+}
+
+#[test]
+fn i2c_recover_bus_drops_pending_responses() {
+    let iowarrior = get_simulated_iowarrior(IOWarriorType::IOWarrior40, 0x2000).unwrap();
+    let control = get_simulator_control(&iowarrior.get_serial_number()).unwrap();
+
+    control.attach_i2c_memory(0x50);
+
+    let mut i2c_config = I2CConfig::default();
+
+    i2c_config.timeout = Some(Duration::from_millis(20));
+
+    let mut i2c = iowarrior.setup_i2c_with_config(i2c_config).unwrap();
+
+    i2c.write(0x50u8, &[0x10, 0xA5]).unwrap();
+
+    control.set_i2c_stalled(true);
+
+    let mut read = [0u8; 1];
+
+    assert!(matches!(
+        i2c.write_read(0x50u8, &[0x10], &mut read),
+        Err(I2CError::Timeout)
+    ));
+
+    i2c.recover_bus().unwrap();
+
+    control.set_i2c_stalled(false);
+
+    i2c.write_read(0x50u8, &[0x10], &mut read).unwrap();
+
+    assert_eq!(read, [0xA5]);
+
+    let mut iowarrior100_i2c = get_simulated_iowarrior(IOWarriorType::IOWarrior100, 0x2000)
+        .unwrap()
+        .setup_i2c()
+        .unwrap();
+
+    assert!(matches!(
+        iowarrior100_i2c.recover_bus(),
+        Err(I2CError::RecoveryNotSupported)
+    ));
+}
+
+#[test]
+fn spi_transfer_reads_back_looped_data() {
+    for device_type in [IOWarriorType::IOWarrior24, IOWarriorType::IOWarrior56] {
+        let iowarrior = get_simulated_iowarrior(device_type, 0x2000).unwrap();
+
+        let mut spi = iowarrior.setup_spi().unwrap();
+
+        // MISO is looped back to MOSI, long transfers span several reports.
+        let written: Vec<u8> = (1..=100).collect();
+        let mut read = [0u8; 100];
+
+        spi.transfer(&mut read, &written).unwrap();
+
+        assert_eq!(read.as_slice(), written.as_slice());
+    }
+}
+
+#[test]
+fn ir_receiver_decodes_frames() {
+    let iowarrior = get_simulated_iowarrior(IOWarriorType::IOWarrior24, 0x2000).unwrap();
+    let control = get_simulator_control(&iowarrior.get_serial_number()).unwrap();
+
+    let mut ir_receiver = iowarrior.setup_ir_receiver().unwrap();
+
+    assert_eq!(ir_receiver.try_read().unwrap(), None);
+
+    let ir_frame = IRFrame {
+        address: 0x15,
+        command: 0x2A,
+        toggle: true,
+    };
+
+    control.receive_ir_frame(ir_frame);
+
+    assert_eq!(ir_receiver.try_read().unwrap(), Some(ir_frame));
+    assert_eq!(
+        ir_receiver.read_timeout(Duration::from_millis(30)).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn ir_frame_is_kept_for_receiver_while_spi_reads() {
+    let iowarrior = get_simulated_iowarrior(IOWarriorType::IOWarrior56, 0x2000).unwrap();
+    let control = get_simulator_control(&iowarrior.get_serial_number()).unwrap();
+
+    let mut ir_receiver = iowarrior.setup_ir_receiver().unwrap();
+    let mut spi = iowarrior.setup_spi().unwrap();
+
+    let ir_frame = IRFrame {
+        address: 0x03,
+        command: 0x11,
+        toggle: false,
+    };
+
+    control.receive_ir_frame(ir_frame);
+
+    let mut read = [0u8; 4];
+
+    spi.transfer(&mut read, &[1, 2, 3, 4]).unwrap();
+
+    assert_eq!(read, [1, 2, 3, 4]);
+    assert_eq!(ir_receiver.read().unwrap(), ir_frame);
+}
+
+#[cfg(feature = "sync")]
+#[test]
+fn waiting_ir_receiver_does_not_starve_other_handles() {
+    let iowarrior = get_simulated_iowarrior(IOWarriorType::IOWarrior24, 0x2000).unwrap();
+    let control = get_simulator_control(&iowarrior.get_serial_number()).unwrap();
+
+    let mut ir_receiver = iowarrior.setup_ir_receiver().unwrap();
+    let mut output_pin = iowarrior.setup_output_as_high(pin!(1, 0)).unwrap();
+
+    let waiter = std::thread::spawn(move || ir_receiver.read());
+
+    std::thread::sleep(Duration::from_millis(20));
+
+    let start = Instant::now();
+
+    for _ in 0..20 {
+        output_pin.set_low().unwrap();
+        output_pin.set_high().unwrap();
+    }
+
+    assert!(start.elapsed() < Duration::from_secs(1));
+
+    let ir_frame = IRFrame {
+        address: 0x1F,
+        command: 0x3F,
+        toggle: false,
+    };
+
+    control.receive_ir_frame(ir_frame);
+
+    assert_eq!(waiter.join().unwrap().unwrap(), ir_frame);
+}
+
+#[test]
+fn waiting_pin_ignores_changes_before_the_call() {
+    let iowarrior = get_simulated_iowarrior(IOWarriorType::IOWarrior40, 0x2000).unwrap();
+    let control = get_simulator_control(&iowarrior.get_serial_number()).unwrap();
+
+    let mut input_pin = iowarrior.setup_input(pin!(0, 1)).unwrap();
+
+    // Both changes are queued before the wait starts, so they are no rising edge.
+    control.set_pin_input_state(pin!(0, 1), PinState::Low);
+    control.set_pin_input_state(pin!(0, 1), PinState::High);
+
+    assert!(matches!(
+        input_pin.wait_for_rising_edge(Some(Duration::from_millis(30))),
+        Err(PinError::Timeout)
+    ));
+
+    control.set_pin_input_state(pin!(0, 1), PinState::Low);
+
+    input_pin
+        .wait_for_low(Some(Duration::from_millis(30)))
+        .unwrap();
+}
+
+#[test]
+fn debounce_counts_only_reports() {
+    let iowarrior = get_simulated_iowarrior(IOWarriorType::IOWarrior24, 0x2000).unwrap();
+    let control = get_simulator_control(&iowarrior.get_serial_number()).unwrap();
+
+    let mut debounced_input = iowarrior
+        .setup_debounced_input(pin!(0, 3), Debounce::Reports(3))
+        .unwrap();
+    let mut output_pin = iowarrior.setup_output_as_high(pin!(1, 0)).unwrap();
+
+    control.set_pin_input_state(pin!(0, 3), PinState::Low);
+
+    // Reads without a new report do not count.
+    for _ in 0..5 {
+        assert!(debounced_input.is_high().unwrap());
+    }
+
+    assert_eq!(debounced_input.try_next_edge().unwrap(), None);
+
+    // Changes of another pin send reports that show the new state again.
+    output_pin.set_low().unwrap();
+    output_pin.set_high().unwrap();
+
+    assert!(debounced_input.is_low().unwrap());
+
+    let pin_event = debounced_input.try_next_edge().unwrap().unwrap();
+
+    assert_eq!(pin_event.pin, pin!(0, 3));
+    assert_eq!(pin_event.pin_state, PinState::Low);
+}
+
+#[cfg(feature = "sync")]
+#[test]
+fn waiting_pin_detects_edge_after_the_call() {
+    let iowarrior = get_simulated_iowarrior(IOWarriorType::IOWarrior40, 0x2000).unwrap();
+    let control = get_simulator_control(&iowarrior.get_serial_number()).unwrap();
+
+    let mut input_pin = iowarrior.setup_input(pin!(0, 1)).unwrap();
+
+    let waiter =
+        std::thread::spawn(move || input_pin.wait_for_falling_edge(Some(Duration::from_secs(2))));
+
+    std::thread::sleep(Duration::from_millis(20));
+
+    control.set_pin_input_state(pin!(0, 1), PinState::Low);
+
+    waiter.join().unwrap().unwrap();
+}
+
+#[cfg(feature = "sync")]
+#[test]
+fn waiting_pin_does_not_starve_other_handles() {
+    let iowarrior = get_simulated_iowarrior(IOWarriorType::IOWarrior40, 0x2000).unwrap();
+
+    let mut input_pin = iowarrior.setup_input(pin!(0, 1)).unwrap();
+    let mut output_pin = iowarrior.setup_output_as_high(pin!(1, 0)).unwrap();
+
+    let waiter =
+        std::thread::spawn(move || input_pin.wait_for_rising_edge(Some(Duration::from_secs(2))));
+
+    std::thread::sleep(Duration::from_millis(20));
+
+    let start = Instant::now();
+
+    for _ in 0..20 {
+        output_pin.set_low().unwrap();
+        output_pin.set_high().unwrap();
+    }
+
+    // Every write waits for at most one slice of the waiting pin.
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert!(waiter.join().unwrap().is_err());
+}
+
+#[cfg(feature = "sync")]
+#[test]
+fn waveform_player_plays_steps_in_order() {
+    let iowarrior = get_simulated_iowarrior(IOWarriorType::IOWarrior40, 0x2000).unwrap();
+
+    let pins = [pin!(1, 0), pin!(1, 1)];
+
+    let mut waveform_player = iowarrior.setup_waveform_player(&pins).unwrap();
+    let pin_monitor = iowarrior.setup_pin_monitor(&pins).unwrap();
+
+    assert!(iowarrior.setup_input(pin!(1, 0)).is_err());
+
+    let step = |mask, value| WaveformStep {
+        mask,
+        value,
+        duration: Duration::from_millis(10),
+    };
+
+    waveform_player.play(vec![step(0b11, 0b00), step(0b01, 0b01), step(0b10, 0b10)]);
+
+    let statistics = waveform_player.wait().unwrap();
+
+    assert_eq!(statistics.steps, 3);
+    assert!(statistics.duration >= Duration::from_millis(30));
+    assert!(!waveform_player.is_playing());
+
+    let events: Vec<(u8, PinState)> = std::iter::from_fn(|| {
+        pin_monitor
+            .next_event_timeout(Duration::from_millis(200))
+            .unwrap()
+    })
+    .map(|x| (x.pin, x.pin_state))
+    .collect();
+
+    assert_eq!(
+        events,
+        [
+            (pin!(1, 0), PinState::Low),
+            (pin!(1, 1), PinState::Low),
+            (pin!(1, 0), PinState::High),
+            (pin!(1, 1), PinState::High),
+        ]
+    );
+
+    // Stopping cancels the remaining steps.
+    waveform_player.play(vec![
+        WaveformStep {
+            mask: 0b01,
+            value: 0b00,
+            duration: Duration::from_secs(5),
+        };
+        3
+    ]);
+
+    std::thread::sleep(Duration::from_millis(20));
+
+    let statistics = waveform_player.stop().unwrap();
+
+    assert_eq!(statistics.steps, 1);
+    assert!(statistics.duration < Duration::from_secs(1));
+}
+
+#[cfg(feature = "sync")]
+#[test]
+fn pin_monitor_logs_changes_of_its_pins() {
+    let iowarrior = get_simulated_iowarrior(IOWarriorType::IOWarrior24, 0x2000).unwrap();
+    let control = get_simulator_control(&iowarrior.get_serial_number()).unwrap();
+
+    let mut pin_monitor = iowarrior
+        .setup_pin_monitor(&[pin!(0, 1), pin!(0, 2)])
+        .unwrap();
+
+    // The monitor does not claim its pins.
+    let mut output_pin = iowarrior.setup_output_as_high(pin!(0, 2)).unwrap();
+
+    let start = Instant::now();
+
+    control.set_pin_input_state(pin!(0, 1), PinState::Low);
+    control.set_pin_input_state(pin!(0, 3), PinState::Low);
+    output_pin.set_low().unwrap();
+    control.set_pin_input_state(pin!(0, 1), PinState::High);
+
+    let mut events = Vec::new();
+
+    while let Some(pin_event) = pin_monitor
+        .next_event_timeout(Duration::from_millis(200))
+        .unwrap()
+    {
+        events.push(pin_event);
+    }
+
+    assert_eq!(
+        events
+            .iter()
+            .map(|x| (x.pin, x.pin_state))
+            .collect::<Vec<_>>(),
+        [
+            (pin!(0, 1), PinState::Low),
+            (pin!(0, 2), PinState::Low),
+            (pin!(0, 1), PinState::High),
+        ]
+    );
+
+    assert!(events.windows(2).all(|x| x[0].instant <= x[1].instant));
+    assert!(events.iter().all(|x| x.instant >= start));
+
+    assert!(pin_monitor.is_running());
+
+    pin_monitor.stop().unwrap();
+
+    assert!(!pin_monitor.is_running());
+    assert!(matches!(
+        pin_monitor.next_event_timeout(Duration::from_millis(10)),
+        Err(PinError::MonitorStopped)
+    ));
+}
+
+#[test]
+fn board_is_reconnected_after_disconnect() {
+    let iowarrior = get_simulated_iowarrior(IOWarriorType::IOWarrior40, 0x2000).unwrap();
+    let control = get_simulator_control(&iowarrior.get_serial_number()).unwrap();
+
+    control.attach_i2c_memory(0x50);
+
+    iowarrior.enable_reconnect(Duration::from_secs(5));
+
+    let mut output_pin = iowarrior.setup_output_as_high(pin!(0, 0)).unwrap();
+    let mut input_pin = iowarrior.setup_input(pin!(0, 1)).unwrap();
+    let mut i2c = iowarrior.setup_i2c().unwrap();
+
+    i2c.write(0x50u8, &[0x10, 0x5A]).unwrap();
+    output_pin.set_low().unwrap();
+
+    control.disconnect();
+
+    assert!(output_pin.set_high().is_err());
+
+    control.connect();
+
+    // Attempts are made at most every 100 ms.
+    std::thread::sleep(Duration::from_millis(150));
+
+    output_pin.set_high().unwrap();
+
+    assert!(input_pin.is_high().unwrap());
+
+    // The I2C setup report is sent again after reopening.
+    let mut read = [0u8; 1];
+
+    i2c.write_read(0x50u8, &[0x10], &mut read).unwrap();
+
+    assert_eq!(read, [0x5A]);
+
+    control.disconnect();
+    iowarrior.disable_reconnect();
+
+    std::thread::sleep(Duration::from_millis(150));
+
+    control.connect();
+
+    assert!(output_pin.set_low().is_err());
+}
+
+#[test]
+fn batch_is_committed_when_closure_panics() {
+    let iowarrior = get_simulated_iowarrior(IOWarriorType::IOWarrior40, 0x2000).unwrap();
+
+    let mut gpio_port = iowarrior
+        .setup_gpio_port(&[pin!(1, 0), pin!(1, 1)])
+        .unwrap();
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        iowarrior.batch(|_| {
+            gpio_port.write(0b10u8).unwrap();
+
+            panic!("closure failed");
+        })
+    }));
+
+    assert!(result.is_err());
+    assert_eq!(gpio_port.read().unwrap(), 0b10);
+
+    gpio_port.write(0b01u8).unwrap();
+
+    assert_eq!(gpio_port.read().unwrap(), 0b01);
+}
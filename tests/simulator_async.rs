@@ -0,0 +1,145 @@
+use embedded_hal::digital::PinState;
+use embedded_hal_async::delay::DelayNs;
+use embedded_hal_async::digital::Wait;
+use embedded_hal_async::i2c::I2c;
+use embedded_hal_async::spi::{Operation, SpiBus, SpiDevice};
+use iowarrior_embedded_hal::communication::simulator::{
+    get_simulated_iowarrior, get_simulator_control,
+};
+use iowarrior_embedded_hal::delay::Delay;
+use iowarrior_embedded_hal::iowarrior::IOWarriorType;
+use iowarrior_embedded_hal::pin;
+use std::future::Future;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::Thread;
+use std::time::{Duration, Instant};
+
+// A minimal executor, the futures only need to be woken from the worker threads.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut context = Context::from_waker(&waker);
+
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+#[test]
+fn async_i2c_round_trip() {
+    let iowarrior = get_simulated_iowarrior(IOWarriorType::IOWarrior100, 0x2000).unwrap();
+    let control = get_simulator_control(&iowarrior.get_serial_number()).unwrap();
+
+    control.attach_i2c_memory(0x50);
+    control.attach_ten_bit_i2c_memory(0x2A5);
+
+    let i2c = iowarrior.setup_i2c().unwrap();
+    let mut shared_i2c = i2c.into_shared();
+    let mut other_i2c = shared_i2c.clone();
+
+    block_on(async {
+        let written: Vec<u8> = (0..100).collect();
+        let mut write_buffer = vec![0x10u8];
+
+        write_buffer.extend_from_slice(&written);
+
+        shared_i2c.write(0x50u8, &write_buffer).await.unwrap();
+        other_i2c.write(0x2A5u16, &[0x10, 0xA5]).await.unwrap();
+
+        let mut read = vec![0u8; written.len()];
+
+        shared_i2c
+            .write_read(0x50u8, &[0x10], &mut read[..1])
+            .await
+            .unwrap();
+        shared_i2c.read(0x50u8, &mut read[1..]).await.unwrap();
+
+        assert_eq!(read, written);
+
+        let mut read = [0u8; 1];
+
+        other_i2c
+            .write_read(0x2A5u16, &[0x10], &mut read)
+            .await
+            .unwrap();
+
+        assert_eq!(read, [0xA5]);
+        assert!(other_i2c.write(0x51u8, &[0x00]).await.is_err());
+    });
+}
+
+#[test]
+fn async_spi_reads_back_looped_data() {
+    let iowarrior = get_simulated_iowarrior(IOWarriorType::IOWarrior56, 0x2000).unwrap();
+
+    let mut spi = iowarrior.setup_spi().unwrap();
+
+    block_on(async {
+        let write: Vec<u8> = (0..100).collect();
+        let mut read = vec![0u8; write.len()];
+
+        SpiBus::transfer(&mut spi, &mut read, &write).await.unwrap();
+
+        assert_eq!(read, write);
+
+        let mut in_place = [0x12u8, 0x34, 0x56];
+        let mut transfer = [0u8; 2];
+
+        SpiDevice::transaction(
+            &mut spi,
+            &mut [
+                Operation::TransferInPlace(&mut in_place),
+                Operation::DelayNs(1_000),
+                Operation::Transfer(&mut transfer, &[0xAB, 0xCD]),
+            ],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(in_place, [0x12, 0x34, 0x56]);
+        assert_eq!(transfer, [0xAB, 0xCD]);
+    });
+}
+
+#[test]
+fn async_wait_detects_edge() {
+    let iowarrior = get_simulated_iowarrior(IOWarriorType::IOWarrior40, 0x2000).unwrap();
+    let control = get_simulator_control(&iowarrior.get_serial_number()).unwrap();
+
+    let mut input_pin = iowarrior.setup_input(pin!(0, 1)).unwrap();
+
+    let puller = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(20));
+
+        control.set_pin_input_state(pin!(0, 1), PinState::Low);
+    });
+
+    block_on(Wait::wait_for_falling_edge(&mut input_pin)).unwrap();
+
+    puller.join().unwrap();
+
+    block_on(Wait::wait_for_low(&mut input_pin)).unwrap();
+}
+
+#[test]
+fn async_delay_waits() {
+    let mut delay = Delay;
+
+    let start = Instant::now();
+
+    block_on(delay.delay_ms(20));
+
+    assert!(start.elapsed() >= Duration::from_millis(20));
+}